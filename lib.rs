@@ -8,15 +8,35 @@ mod az_safe_send {
     use ink::{
         codegen::EmitEvent,
         env::call::{build_call, ExecutionInput, Selector},
-        env::CallFlags,
+        env::{hash::Blake2x256, CallFlags},
         prelude::string::{String, ToString},
-        prelude::vec,
+        prelude::{vec, vec::Vec},
+        primitives::KeyComposer,
         reflect::ContractEventBase,
-        storage::Mapping,
+        storage::{traits::ManualKey, Mapping},
     };
     use openbrush::contracts::psp22::PSP22Ref;
 
+    // === CONSTANTS ===
+    const AZERO_ID_GET_ADDRESS_SELECTOR: [u8; 4] = ink::selector_bytes!("get_address");
+    // A well formed domain that a router is asked to resolve to check that it responds.
+    // It doesn't matter whether it's registered.
+    const AZERO_ID_PROBE_DOMAIN: &str = "az-safe-send-probe.azero";
+    // Cheques are stored under a new key so that they can't be mixed up with the ones
+    // stored by the original release, which remain under the original key
+    const CHEQUES_KEY: u32 = KeyComposer::from_str("AzSafeSend::cheques_v2");
+    const COLLECT_WITH_SIGNATURE_DOMAIN: &[u8] = b"az_safe_send::collect_with_signature";
+    // 30 days in ms
+    const DISPUTE_TIMEOUT: Timestamp = 2_592_000_000;
+    const LEGACY_CHEQUES_KEY: u32 = KeyComposer::from_str("AzSafeSend::cheques");
+    const MAX_APPROVERS: usize = 10;
+    const MAX_BATCH_SIZE: usize = 50;
+    const MAX_CLAIM_COMMITMENTS: usize = 10;
+    const MAX_ENDORSEMENTS: usize = 10;
+    const MAX_RECIPIENTS: usize = 10;
+
     // === TYPES ===
+    type ClaimCommitment = (Hash, BlockNumber);
     type Event = <AzSafeSend as ContractEventBase>::Type;
     type Result<T> = core::result::Result<T, AzSafeSendError>;
 
@@ -28,25 +48,166 @@ mod az_safe_send {
         #[ink(topic)]
         from: AccountId,
         #[ink(topic)]
-        to: AccountId,
+        to: Option<AccountId>,
         amount: Balance,
         token_address: Option<AccountId>,
         fee: Balance,
         memo: Option<String>,
         recipient_azero_id: Option<String>,
         sender_azero_id: Option<String>,
+        expires_at: Option<Timestamp>,
+        unlock_at: Option<Timestamp>,
+        vest_start: Option<Timestamp>,
+        vest_end: Option<Timestamp>,
+        claim_hash: Option<Hash>,
+        recipients: Vec<(AccountId, Balance, Option<String>)>,
+        period: Option<Timestamp>,
+        approvers: Vec<AccountId>,
+        approval_threshold: u32,
+        arbiter: Option<AccountId>,
+        arbiter_fee: Balance,
+        refund_to: Option<AccountId>,
+        revalidate_azero_id: bool,
+    }
+
+    #[ink(event)]
+    pub struct AddResolver {
+        #[ink(topic)]
+        tld: String,
+        address: AccountId,
+        selector: [u8; 4],
+        return_type: ResolverReturnType,
+    }
+
+    #[ink(event)]
+    pub struct Approve {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        approver: AccountId,
     }
 
     #[ink(event)]
     pub struct Cancel {
         #[ink(topic)]
         id: u32,
+        recipient_azero_id: Option<String>,
+        sender_azero_id: Option<String>,
     }
 
     #[ink(event)]
     pub struct Collect {
         #[ink(topic)]
         id: u32,
+        amount: Balance,
+        #[ink(topic)]
+        beneficiary: AccountId,
+        recipient_azero_id: Option<String>,
+        sender_azero_id: Option<String>,
+    }
+
+    #[ink(event)]
+    pub struct Claim {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        beneficiary: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct CommitClaim {
+        #[ink(topic)]
+        id: u32,
+        commitment: Hash,
+    }
+
+    #[ink(event)]
+    pub struct Endorse {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        to_azero_id: Option<String>,
+    }
+
+    #[ink(event)]
+    pub struct Migrate {
+        ids: Vec<u32>,
+    }
+
+    #[ink(event)]
+    pub struct OpenDispute {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Redirect {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        old_to: AccountId,
+        #[ink(topic)]
+        new_to: AccountId,
+        recipient_azero_id: Option<String>,
+    }
+
+    #[ink(event)]
+    pub struct RefundExpired {
+        #[ink(topic)]
+        id: u32,
+    }
+
+    #[ink(event)]
+    pub struct Reject {
+        #[ink(topic)]
+        id: u32,
+        reason: Option<String>,
+    }
+
+    #[ink(event)]
+    pub struct RemoveResolver {
+        #[ink(topic)]
+        tld: String,
+    }
+
+    #[ink(event)]
+    pub struct Resolve {
+        #[ink(topic)]
+        id: u32,
+        to_recipient: Balance,
+        to_sender: Balance,
+        arbiter_fee: Balance,
+    }
+
+    #[ink(event)]
+    pub struct RevokeApproval {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        approver: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct SetCode {
+        code_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct TopUp {
+        #[ink(topic)]
+        id: u32,
+        extra_amount: Balance,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct UpdateAzeroIdRouterAddress {
+        azero_id_router_address: AccountId,
     }
 
     #[ink(event)]
@@ -54,6 +215,46 @@ mod az_safe_send {
         fee: Balance,
     }
 
+    #[ink(event)]
+    pub struct UpdateMemo {
+        #[ink(topic)]
+        id: u32,
+        memo: Option<String>,
+    }
+
+    // === ENUMS ===
+    // Discriminants match the u8 values that status was stored as by the original release.
+    // Cheques stored by it have a different layout and are decoded as LegacyCheque instead.
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ChequeStatus {
+        PendingCollection = 0,
+        Collected = 1,
+        Cancelled = 2,
+        Expired = 3,
+        Rejected = 4,
+        Disputed = 5,
+        Resolved = 6,
+    }
+
+    // How a resolver's response is decoded into an address
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum ResolverReturnType {
+        // Result<AccountId, u8>, as returned by the AZERO.ID router
+        AccountIdResult,
+        // Option<AccountId>
+        OptionalAccountId,
+        // AccountId
+        AccountId,
+    }
+
     // === STRUCTS ===
     #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
     #[cfg_attr(
@@ -61,6 +262,156 @@ mod az_safe_send {
         derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
     )]
     pub struct Cheque {
+        id: u32,
+        from: AccountId,
+        to: Option<AccountId>,
+        amount: Balance,
+        token_address: Option<AccountId>,
+        status: ChequeStatus,
+        fee: Balance,
+        memo: Option<String>,
+        expires_at: Option<Timestamp>,
+        unlock_at: Option<Timestamp>,
+        vest_start: Option<Timestamp>,
+        vest_end: Option<Timestamp>,
+        collected_amount: Balance,
+        beneficiary: Option<AccountId>,
+        endorsements: Vec<AccountId>,
+        claim_hash: Option<Hash>,
+        // Split cheques are paid out to several recipients, each collecting their own share
+        recipients: Vec<(AccountId, Balance)>,
+        collected_recipients: Vec<AccountId>,
+        // Recurring cheques vest in instalments, one every period, with a fee per instalment
+        period: Option<Timestamp>,
+        // Collection requires approval_threshold of the approvers to have approved
+        approvers: Vec<AccountId>,
+        approval_threshold: u32,
+        approvals: Vec<AccountId>,
+        // Either party can open a dispute for the arbiter to resolve, for which the arbiter
+        // is paid arbiter_fee out of the uncollected amount
+        arbiter: Option<AccountId>,
+        arbiter_fee: Balance,
+        // Disputes the arbiter hasn't resolved within DISPUTE_TIMEOUT can be refunded
+        disputed_at: Option<Timestamp>,
+        // Refunds go here instead of to from if set
+        refund_to: Option<AccountId>,
+        // If there's no to, the cheque is payable to whoever the domain resolves to on collection
+        recipient_azero_id: Option<String>,
+        // Collection is refused if the domain no longer resolves to to
+        revalidate_azero_id: bool,
+        sender_azero_id: Option<String>,
+    }
+    impl Cheque {
+        // The fee due for collecting amount on top of what has already been collected.
+        // Recurring cheques pay the fee for each instalment as it's fully collected,
+        // other cheques pay the whole fee on the first collection.
+        fn fee_due(&self, amount: Balance) -> Balance {
+            if self.period.is_some() {
+                let instalment_amount: Balance = self.amount / self.instalments();
+                let instalments_collected: Balance = (self.collected_amount + amount)
+                    / instalment_amount
+                    - self.collected_amount / instalment_amount;
+                self.fee / self.instalments() * instalments_collected
+            } else if self.collected_amount == 0 {
+                self.fee
+            } else {
+                0
+            }
+        }
+
+        fn dispute_has_timed_out(&self, timestamp: Timestamp) -> bool {
+            if let Some(disputed_at) = self.disputed_at {
+                timestamp >= disputed_at.saturating_add(DISPUTE_TIMEOUT)
+            } else {
+                false
+            }
+        }
+
+        fn has_expired(&self, timestamp: Timestamp) -> bool {
+            if let Some(expires_at) = self.expires_at {
+                timestamp >= expires_at
+            } else {
+                false
+            }
+        }
+
+        fn is_approved(&self) -> bool {
+            self.approvals.len() >= self.approval_threshold as usize
+        }
+
+        fn is_locked(&self, timestamp: Timestamp) -> bool {
+            if let Some(unlock_at) = self.unlock_at {
+                timestamp < unlock_at
+            } else {
+                false
+            }
+        }
+
+        fn instalments(&self) -> Balance {
+            if let (Some(period), Some(vest_start), Some(vest_end)) =
+                (self.period, self.vest_start, self.vest_end)
+            {
+                ((vest_end - vest_start) / period).into()
+            } else {
+                1
+            }
+        }
+
+        fn is_split(&self) -> bool {
+            !self.recipients.is_empty()
+        }
+
+        fn is_vesting(&self) -> bool {
+            self.vest_start.is_some() && self.vest_end.is_some()
+        }
+
+        fn vested_amount(&self, timestamp: Timestamp) -> Balance {
+            if let (Some(vest_start), Some(vest_end)) = (self.vest_start, self.vest_end) {
+                if timestamp <= vest_start {
+                    0
+                } else if timestamp >= vest_end {
+                    self.amount
+                } else {
+                    // Recurring cheques only vest at the end of each period
+                    let mut elapsed: Timestamp = timestamp - vest_start;
+                    if let Some(period) = self.period {
+                        elapsed -= elapsed % period;
+                    }
+                    // Split amount * elapsed / duration so that it can't overflow
+                    let elapsed: Balance = elapsed.into();
+                    let duration: Balance = (vest_end - vest_start).into();
+                    self.amount / duration * elapsed + self.amount % duration * elapsed / duration
+                }
+            } else {
+                self.amount
+            }
+        }
+
+        fn refund_address(&self) -> AccountId {
+            self.refund_to.unwrap_or(self.from)
+        }
+
+        // The fee that hasn't been earned if retained_amount is all the recipient gets
+        fn unearned_fee(&self, retained_amount: Balance) -> Balance {
+            if self.period.is_some() {
+                let instalments_retained: Balance =
+                    retained_amount / (self.amount / self.instalments());
+                self.fee / self.instalments() * (self.instalments() - instalments_retained)
+            } else if retained_amount == 0 {
+                self.fee
+            } else {
+                0
+            }
+        }
+    }
+
+    // The layout cheques were stored with by the original release
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct LegacyCheque {
         id: u32,
         from: AccountId,
         to: AccountId,
@@ -70,6 +421,68 @@ mod az_safe_send {
         fee: Balance,
         memo: Option<String>,
     }
+    impl From<LegacyCheque> for Cheque {
+        fn from(legacy_cheque: LegacyCheque) -> Self {
+            Cheque {
+                id: legacy_cheque.id,
+                from: legacy_cheque.from,
+                to: Some(legacy_cheque.to),
+                amount: legacy_cheque.amount,
+                token_address: legacy_cheque.token_address,
+                status: match legacy_cheque.status {
+                    0 => ChequeStatus::PendingCollection,
+                    1 => ChequeStatus::Collected,
+                    _ => ChequeStatus::Cancelled,
+                },
+                fee: legacy_cheque.fee,
+                memo: legacy_cheque.memo,
+                expires_at: None,
+                unlock_at: None,
+                vest_start: None,
+                vest_end: None,
+                collected_amount: 0,
+                beneficiary: None,
+                endorsements: vec![],
+                claim_hash: None,
+                recipients: vec![],
+                collected_recipients: vec![],
+                period: None,
+                approvers: vec![],
+                approval_threshold: 0,
+                approvals: vec![],
+                arbiter: None,
+                arbiter_fee: 0,
+                disputed_at: None,
+                refund_to: None,
+                recipient_azero_id: None,
+                revalidate_azero_id: false,
+                sender_azero_id: None,
+            }
+        }
+    }
+
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct ChequeParams {
+        to: Option<AccountId>,
+        amount: Balance,
+        token_address: Option<AccountId>,
+        memo: Option<String>,
+        recipient_azero_id: Option<String>,
+        sender_azero_id: Option<String>,
+        expires_at: Option<Timestamp>,
+        unlock_at: Option<Timestamp>,
+        vest_start: Option<Timestamp>,
+        vest_end: Option<Timestamp>,
+        claim_hash: Option<Hash>,
+        period: Option<Timestamp>,
+        approvers: Vec<AccountId>,
+        approval_threshold: u32,
+        arbiter: Option<AccountId>,
+        arbiter_fee: Balance,
+        refund_to: Option<AccountId>,
+        revalidate_azero_id: bool,
+    }
 
     #[derive(Debug, Clone, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -80,13 +493,31 @@ mod az_safe_send {
         azero_id_router_address: AccountId,
     }
 
+    #[derive(scale::Decode, scale::Encode, Debug, Clone, PartialEq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Resolver {
+        address: AccountId,
+        selector: [u8; 4],
+        return_type: ResolverReturnType,
+    }
+
     #[ink(storage)]
     pub struct AzSafeSend {
         fee: Balance,
         admin: AccountId,
-        cheques: Mapping<u32, Cheque>,
+        cheques: Mapping<u32, Cheque, ManualKey<CHEQUES_KEY>>,
         cheques_total: u32,
         azero_id_router_address: AccountId,
+        // Commitments to claim each claim link, with the block they were made in
+        claim_commitments: Mapping<u32, Vec<ClaimCommitment>>,
+        nonces: Mapping<AccountId, u32>,
+        // Domains with a TLD that isn't in here are resolved by the AZERO.ID router
+        resolvers: Mapping<String, Resolver>,
+        // Cheques stored by the original release that haven't been migrated yet
+        legacy_cheques: Mapping<u32, LegacyCheque, ManualKey<LEGACY_CHEQUES_KEY>>,
     }
     impl AzSafeSend {
         #[ink(constructor)]
@@ -97,6 +528,10 @@ mod az_safe_send {
                 cheques: Mapping::default(),
                 cheques_total: 0,
                 azero_id_router_address,
+                claim_commitments: Mapping::default(),
+                nonces: Mapping::default(),
+                resolvers: Mapping::default(),
+                legacy_cheques: Mapping::default(),
             }
         }
 
@@ -111,316 +546,4692 @@ mod az_safe_send {
             }
         }
 
+        // blake2x256 of the SCALE encoded (id, secret, beneficiary)
+        #[ink(message)]
+        pub fn claim_commitment(&self, id: u32, secret: Vec<u8>, beneficiary: AccountId) -> Hash {
+            Hash::from(
+                self.env()
+                    .hash_encoded::<Blake2x256, _>(&(id, secret, beneficiary)),
+            )
+        }
+
+        // The hash that the recipient signs to authorise collect_with_signature
+        #[ink(message)]
+        pub fn collect_signature_hash(
+            &self,
+            id: u32,
+            beneficiary: AccountId,
+            deadline: Timestamp,
+            tip: Balance,
+        ) -> Result<Hash> {
+            let cheque: Cheque = self.show(id)?;
+
+            Ok(Hash::from(self.collect_signature_message_hash(
+                &cheque,
+                beneficiary,
+                deadline,
+                tip,
+            )?))
+        }
+
+        #[ink(message)]
+        pub fn nonce(&self, account: AccountId) -> u32 {
+            self.nonces.get(account).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn resolver(&self, tld: String) -> Result<Resolver> {
+            self.resolvers
+                .get(Self::normalize_tld(tld)?)
+                .ok_or(AzSafeSendError::NotFound("Resolver".to_string()))
+        }
+
         #[ink(message)]
         pub fn show(&self, id: u32) -> Result<Cheque> {
             if let Some(cheque) = self.cheques.get(id) {
                 Ok(cheque)
+            } else if let Some(legacy_cheque) = self.legacy_cheques.get(id) {
+                Ok(legacy_cheque.into())
             } else {
                 Err(AzSafeSendError::NotFound("Cheque".to_string()))
             }
         }
 
         // === HANDLES ===
+        // Adds or replaces the resolver for domains ending in .tld.
+        // The resolver must respond to the selector for probe_domain before it's accepted.
+        // Resolvers that return a plain AccountId may revert for a domain that doesn't exist,
+        // so probe_domain should be one that does.
         #[ink(message)]
-        pub fn cancel(&mut self, id: u32) -> Result<Cheque> {
-            let mut cheque: Cheque = self.show(id)?;
-            let caller: AccountId = Self::env().caller();
-            if caller != cheque.from {
+        pub fn add_resolver(
+            &mut self,
+            tld: String,
+            address: AccountId,
+            selector: [u8; 4],
+            return_type: ResolverReturnType,
+            probe_domain: String,
+        ) -> Result<()> {
+            if Self::env().caller() != self.admin {
                 return Err(AzSafeSendError::Unauthorised);
             }
-            if cheque.status != 0 {
-                return Err(AzSafeSendError::UnprocessableEntity(
-                    "Status must be pending collection.".to_string(),
-                ));
-            }
-
-            let mut azero_to_return_to_user: Balance = 0;
-            // Return amount to caller
-            if let Some(token_address_unwrapped) = cheque.token_address {
-                PSP22Ref::transfer_builder(&token_address_unwrapped, caller, cheque.amount, vec![])
-                    .call_flags(CallFlags::default())
-                    .invoke()?;
-            } else {
-                azero_to_return_to_user += cheque.amount
-            }
-
-            // Return fee to caller
-            azero_to_return_to_user += cheque.fee;
-            if azero_to_return_to_user > 0
-                && self
-                    .env()
-                    .transfer(caller, azero_to_return_to_user)
-                    .is_err()
-            {
-                panic!(
-                    "requested transfer failed. this can be the case if the contract does not\
-                         have sufficient free funds or if the transfer would have brought the\
-                         contract's balance below minimum balance."
-                )
-            }
+            let tld: String = Self::normalize_tld(tld)?;
+            let resolver: Resolver = Resolver {
+                address,
+                selector,
+                return_type,
+            };
+            // Any response will do, whether or not the domain resolves to an address
+            let _ = self.resolve_domain(&resolver, probe_domain)?;
 
-            // Update cheque
-            cheque.status = 2;
-            self.cheques.insert(cheque.id, &cheque);
+            self.resolvers.insert(tld.clone(), &resolver);
 
             // emit event
-            Self::emit_event(self.env(), Event::Cancel(Cancel { id: cheque.id }));
+            Self::emit_event(
+                self.env(),
+                Event::AddResolver(AddResolver {
+                    tld,
+                    address,
+                    selector,
+                    return_type,
+                }),
+            );
 
-            Ok(cheque)
+            Ok(())
         }
 
         #[ink(message)]
-        pub fn collect(&mut self, id: u32) -> Result<Cheque> {
+        pub fn approve(&mut self, id: u32) -> Result<Cheque> {
             let mut cheque: Cheque = self.show(id)?;
             let caller: AccountId = Self::env().caller();
-            if caller != cheque.to {
+            if !cheque.approvers.contains(&caller) {
                 return Err(AzSafeSendError::Unauthorised);
             }
-            if cheque.status != 0 {
+            if cheque.status != ChequeStatus::PendingCollection {
                 return Err(AzSafeSendError::UnprocessableEntity(
                     "Status must be pending collection.".to_string(),
                 ));
             }
-
-            if let Some(token_address_unwrapped) = cheque.token_address {
-                // Transfer token to amount
-                PSP22Ref::transfer_builder(&token_address_unwrapped, caller, cheque.amount, vec![])
-                    .call_flags(CallFlags::default())
-                    .invoke()?;
-            } else if self.env().transfer(caller, cheque.amount).is_err() {
-                panic!(
-                    "requested transfer failed. this can be the case if the contract does not\
-                             have sufficient free funds or if the transfer would have brought the\
-                             contract's balance below minimum balance."
-                )
-            }
-
-            // transfer fee to admin
-            if cheque.fee > 0 && self.env().transfer(self.admin, cheque.fee).is_err() {
-                panic!(
-                    "requested transfer failed. this can be the case if the contract does not\
-                             have sufficient free funds or if the transfer would have brought the\
-                             contract's balance below minimum balance."
-                )
+            if cheque.approvals.contains(&caller) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has already been approved by caller.".to_string(),
+                ));
             }
 
-            // set status
-            cheque.status = 1;
+            // Update cheque
+            cheque.approvals.push(caller);
             self.cheques.insert(cheque.id, &cheque);
 
             // emit event
-            Self::emit_event(self.env(), Event::Collect(Collect { id: cheque.id }));
+            Self::emit_event(
+                self.env(),
+                Event::Approve(Approve {
+                    id: cheque.id,
+                    approver: caller,
+                }),
+            );
 
             Ok(cheque)
         }
 
-        // 0 == Pending Collection
-        // 1 == Collected
-        // 2 == Cancelled
-        #[ink(message, payable)]
-        pub fn create(
-            &mut self,
-            to: AccountId,
-            amount: Balance,
-            token_address: Option<AccountId>,
-            memo: Option<String>,
-            recipient_azero_id: Option<String>,
-            sender_azero_id: Option<String>,
-        ) -> Result<Cheque> {
+        #[ink(message)]
+        pub fn cancel(&mut self, id: u32) -> Result<Cheque> {
+            let cheque: Cheque = self.show(id)?;
+            if Self::env().caller() != cheque.from {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+            let (cheque, amount_to_return, fee_to_return) = self.record_cancellation(cheque)?;
+            self.return_to_sender(&cheque, amount_to_return, fee_to_return)?;
+
+            Ok(cheque)
+        }
+
+        // Cancels each cheque, refunding the caller with a single transfer per token.
+        // Failures are returned per id, unless strict is set in which case the first
+        // failure aborts the whole batch.
+        #[ink(message)]
+        pub fn cancel_many(&mut self, ids: Vec<u32>, strict: bool) -> Result<Vec<Result<Cheque>>> {
+            self.validate_batch_size(ids.len())?;
             let caller: AccountId = Self::env().caller();
-            if caller == to {
+            let mut results: Vec<Result<Cheque>> = vec![];
+            let mut refunds: Vec<(AccountId, Option<AccountId>, Balance)> = vec![];
+            for id in ids {
+                let result = self.show(id).and_then(|cheque| {
+                    if caller != cheque.from {
+                        return Err(AzSafeSendError::Unauthorised);
+                    }
+                    self.record_cancellation(cheque)
+                });
+                match result {
+                    Ok((cheque, amount_to_return, fee_to_return)) => {
+                        let refund_to: AccountId = cheque.refund_address();
+                        Self::add_to_payouts(
+                            &mut refunds,
+                            refund_to,
+                            cheque.token_address,
+                            amount_to_return,
+                        )?;
+                        Self::add_to_payouts(&mut refunds, refund_to, None, fee_to_return)?;
+                        results.push(Ok(cheque));
+                    }
+                    Err(e) => {
+                        if strict {
+                            return Err(e);
+                        }
+                        results.push(Err(e));
+                    }
+                }
+            }
+            self.pay_out(refunds)?;
+
+            Ok(results)
+        }
+
+        // Pays a claim link out to a beneficiary committed to via commit_claim.
+        // The revealed secret is public from then on, so the cheque is addressed to
+        // the beneficiary for any later collections.
+        #[ink(message)]
+        pub fn claim(
+            &mut self,
+            id: u32,
+            secret: Vec<u8>,
+            beneficiary: AccountId,
+        ) -> Result<Cheque> {
+            let mut cheque: Cheque = self.show(id)?;
+            if cheque.claim_hash.is_none() {
                 return Err(AzSafeSendError::UnprocessableEntity(
-                    "Sender and receiver must be different.".to_string(),
+                    "Cheque is not a claim link.".to_string(),
                 ));
             }
-            if let Some(recipient_azero_id_unwrapped) = recipient_azero_id.clone() {
-                self.validate_ownership_of_azero_id(recipient_azero_id_unwrapped, to)?;
-            }
-            if let Some(sender_azero_id_unwrapped) = sender_azero_id.clone() {
-                self.validate_ownership_of_azero_id(sender_azero_id_unwrapped, caller)?;
-            }
-            if amount == 0 {
+            self.validate_beneficiary(beneficiary)?;
+            if cheque.status != ChequeStatus::PendingCollection {
                 return Err(AzSafeSendError::UnprocessableEntity(
-                    "Amount must be greater than zero.".to_string(),
+                    "Status must be pending collection.".to_string(),
                 ));
             }
-            if self.cheques_total == u32::MAX {
-                return Err(AzSafeSendError::RecordsLimitReached("Cheque".to_string()));
+            let block_timestamp: Timestamp = self.env().block_timestamp();
+            if cheque.has_expired(block_timestamp) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has expired.".to_string(),
+                ));
             }
-            if token_address.is_some() {
-                // Check AZERO sent in equals fee if token
-                if self.env().transferred_value() != self.fee {
-                    return Err(AzSafeSendError::IncorrectFee);
+            let commitment: Hash = self.claim_commitment(id, secret.clone(), beneficiary);
+            if let Some((_, committed_at)) = self
+                .claim_commitments
+                .get(id)
+                .unwrap_or_default()
+                .into_iter()
+                .find(|(claim_commitment, _)| *claim_commitment == commitment)
+            {
+                if committed_at >= self.env().block_number() {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Claim must be committed in an earlier block.".to_string(),
+                    ));
                 }
-
-                // Transfer token from caller to contract
-                self.acquire_psp22(token_address.unwrap(), caller, amount)?;
             } else {
-                // Check AZERO sent in equals fee + amount if no token_address
-                if self.fee.checked_add(amount).is_none()
-                    || self.env().transferred_value() != self.fee + amount
-                {
-                    return Err(AzSafeSendError::IncorrectFee);
-                }
+                return Err(AzSafeSendError::NotFound("Commitment".to_string()));
+            }
+            let secret_hash: Hash = Hash::from(self.env().hash_bytes::<Blake2x256>(&secret));
+            if cheque.claim_hash != Some(secret_hash) {
+                return Err(AzSafeSendError::Unauthorised);
             }
 
-            let cheque: Cheque = Cheque {
-                id: self.cheques_total,
-                from: caller,
-                to,
-                amount,
-                token_address,
-                status: 0,
-                fee: self.fee,
-                memo,
-            };
-            self.cheques.insert(self.cheques_total, &cheque);
-            self.cheques_total += 1;
+            // Update cheque
+            // The other commitments can't be used once the link has been claimed
+            self.claim_commitments.remove(id);
+            cheque.to = Some(beneficiary);
+            cheque.claim_hash = None;
+            self.cheques.insert(cheque.id, &cheque);
 
             // emit event
             Self::emit_event(
                 self.env(),
-                Event::Create(Create {
+                Event::Claim(Claim {
                     id: cheque.id,
-                    from: cheque.from,
-                    to: cheque.to,
-                    amount: cheque.amount,
-                    token_address: cheque.token_address,
-                    fee: cheque.fee,
-                    memo: cheque.memo.clone(),
-                    recipient_azero_id,
-                    sender_azero_id,
+                    beneficiary,
                 }),
             );
 
+            // Collect whatever can be collected now
+            if !cheque.is_locked(block_timestamp)
+                && cheque.is_approved()
+                && cheque.vested_amount(block_timestamp) > cheque.collected_amount
+            {
+                cheque = self.process_collection(cheque, beneficiary, None)?;
+            }
+
             Ok(cheque)
         }
 
         #[ink(message)]
-        pub fn update_fee(&mut self, fee: Balance) -> Result<()> {
-            if Self::env().caller() != self.admin {
-                return Err(AzSafeSendError::Unauthorised);
+        pub fn collect(&mut self, id: u32) -> Result<Cheque> {
+            let cheque: Cheque = self.show(id)?;
+            let caller: AccountId = Self::env().caller();
+            let (cheque, amount, fee) = self.record_recipient_collection(cheque, caller)?;
+            self.transfer(cheque.token_address, caller, amount)?;
+            self.transfer_azero(self.admin, fee);
+
+            Ok(cheque)
+        }
+
+        // Collects everything collectable from each cheque, with a single transfer per token
+        // to the caller and a single fee transfer to the admin.
+        // Failures are returned per id, unless strict is set in which case the first
+        // failure aborts the whole batch.
+        #[ink(message)]
+        pub fn collect_many(&mut self, ids: Vec<u32>, strict: bool) -> Result<Vec<Result<Cheque>>> {
+            self.validate_batch_size(ids.len())?;
+            let caller: AccountId = Self::env().caller();
+            let mut results: Vec<Result<Cheque>> = vec![];
+            let mut payouts: Vec<(AccountId, Option<AccountId>, Balance)> = vec![];
+            let mut fees: Balance = 0;
+            for id in ids {
+                let result = self
+                    .show(id)
+                    .and_then(|cheque| self.record_recipient_collection(cheque, caller));
+                match result {
+                    Ok((cheque, amount, fee)) => {
+                        Self::add_to_payouts(&mut payouts, caller, cheque.token_address, amount)?;
+                        fees =
+                            fees.checked_add(fee)
+                                .ok_or(AzSafeSendError::UnprocessableEntity(
+                                    "Amount is too large.".to_string(),
+                                ))?;
+                        results.push(Ok(cheque));
+                    }
+                    Err(e) => {
+                        if strict {
+                            return Err(e);
+                        }
+                        results.push(Err(e));
+                    }
+                }
             }
+            self.pay_out(payouts)?;
+            self.transfer_azero(self.admin, fees);
 
-            self.fee = fee;
+            Ok(results)
+        }
 
-            // emit event
-            Self::emit_event(self.env(), Event::UpdateFee(UpdateFee { fee }));
+        #[ink(message)]
+        pub fn collect_partial(&mut self, id: u32, amount: Balance) -> Result<Cheque> {
+            let cheque: Cheque = self.show(id)?;
+            let caller: AccountId = Self::env().caller();
+            self.validate_recipient(&cheque, caller)?;
 
-            Ok(())
+            self.process_collection(cheque, caller, Some(amount))
         }
 
-        // === PRIVATE ===
-        fn acquire_psp22(&self, token: AccountId, from: AccountId, amount: Balance) -> Result<()> {
-            PSP22Ref::transfer_from_builder(&token, from, self.env().account_id(), amount, vec![])
-                .call_flags(CallFlags::default())
-                .invoke()?;
+        #[ink(message)]
+        pub fn collect_to(&mut self, id: u32, beneficiary: AccountId) -> Result<Cheque> {
+            let cheque: Cheque = self.show(id)?;
+            self.validate_recipient(&cheque, Self::env().caller())?;
+            self.validate_beneficiary(beneficiary)?;
 
-            Ok(())
+            self.process_collection(cheque, beneficiary, None)
         }
 
-        // See smart contract hub contract for testing
-        fn address_by_azero_id(&self, domain: String) -> Result<AccountId> {
-            const GET_ADDRESS_SELECTOR: [u8; 4] = ink::selector_bytes!("get_address");
-            let result = build_call::<Environment>()
-                .call(self.azero_id_router_address)
-                .exec_input(
-                    ExecutionInput::new(Selector::new(GET_ADDRESS_SELECTOR)).push_arg(domain),
-                )
-                .returns::<core::result::Result<AccountId, u8>>()
-                .params()
-                .invoke();
-            // Use the result as per the need
-            if let Ok(address) = result {
-                Ok(address)
-            } else {
-                Err(AzSafeSendError::NotFound("Domain".to_string()))
+        // Lets a relayer collect on behalf of a recipient without AZERO for gas.
+        // The recipient must be an ECDSA account, i.e. blake2x256 of its public key,
+        // and signs collect_signature_hash. ink 4 can't verify sr25519 signatures.
+        // The tip is paid to the relayer out of the collectable amount.
+        #[ink(message)]
+        pub fn collect_with_signature(
+            &mut self,
+            id: u32,
+            beneficiary: AccountId,
+            deadline: Timestamp,
+            tip: Balance,
+            signature: [u8; 65],
+        ) -> Result<Cheque> {
+            let cheque: Cheque = self.show(id)?;
+            self.validate_beneficiary(beneficiary)?;
+            let block_timestamp: Timestamp = self.env().block_timestamp();
+            if block_timestamp > deadline {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Signature has expired.".to_string(),
+                ));
+            }
+            let message_hash: [u8; 32] =
+                self.collect_signature_message_hash(&cheque, beneficiary, deadline, tip)?;
+            let public_key: [u8; 33] = self.env().ecdsa_recover(&signature, &message_hash)?;
+            let signer: AccountId =
+                AccountId::from(self.env().hash_bytes::<Blake2x256>(&public_key));
+            self.validate_recipient(&cheque, signer)?;
+            let collectable_amount: Balance = cheque
+                .vested_amount(block_timestamp)
+                .saturating_sub(cheque.collected_amount);
+            if tip > 0 && tip >= collectable_amount {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Tip must be less than collectable amount.".to_string(),
+                ));
             }
-        }
 
-        fn emit_event<EE: EmitEvent<Self>>(emitter: EE, event: Event) {
-            emitter.emit_event(event);
+            // Prevent the signature from being replayed
+            self.nonces.insert(signer, &(self.nonce(signer) + 1));
+
+            let (cheque, amount, fee) = self.record_collection(cheque, beneficiary, None)?;
+            self.transfer(cheque.token_address, beneficiary, amount - tip)?;
+            self.transfer(cheque.token_address, Self::env().caller(), tip)?;
+            self.transfer_azero(self.admin, fee);
+
+            Ok(cheque)
         }
 
-        fn validate_ownership_of_azero_id(
-            &self,
-            azero_id: String,
-            address: AccountId,
-        ) -> Result<()> {
-            if address != self.address_by_azero_id(azero_id)? {
+        // Binds a future claim of a claim link to a beneficiary, so that the secret
+        // can't be front-run once it's revealed.
+        // Commitments can only be made while the claim link can be claimed, and the caller
+        // pays the storage deposit for each one.
+        // They're removed once the link is claimed, cancelled or refunded.
+        #[ink(message)]
+        pub fn commit_claim(&mut self, id: u32, commitment: Hash) -> Result<()> {
+            let cheque: Cheque = self.show(id)?;
+            if cheque.claim_hash.is_none() {
                 return Err(AzSafeSendError::UnprocessableEntity(
-                    "Domain does not belong to address".to_string(),
+                    "Cheque is not a claim link.".to_string(),
+                ));
+            }
+            if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            }
+            if cheque.has_expired(self.env().block_timestamp()) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has expired.".to_string(),
+                ));
+            }
+            let mut claim_commitments: Vec<ClaimCommitment> =
+                self.claim_commitments.get(id).unwrap_or_default();
+            if claim_commitments
+                .iter()
+                .any(|(claim_commitment, _)| *claim_commitment == commitment)
+            {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Commitment already exists.".to_string(),
                 ));
             }
+            if claim_commitments.len() >= MAX_CLAIM_COMMITMENTS {
+                return Err(AzSafeSendError::RecordsLimitReached(
+                    "Commitment".to_string(),
+                ));
+            }
+
+            claim_commitments.push((commitment, self.env().block_number()));
+            self.claim_commitments.insert(id, &claim_commitments);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::CommitClaim(CommitClaim { id, commitment }),
+            );
 
             Ok(())
         }
-    }
-
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        use ink::env::{
-            test::{default_accounts, set_caller, DefaultAccounts},
-            DefaultEnvironment,
-        };
 
-        // === CONSTANTS ===
-        const MOCK_AMOUNT: Balance = 250;
-        const MOCK_FEE: Balance = 500;
+        #[allow(clippy::too_many_arguments)]
+        #[ink(message, payable)]
+        pub fn create(
+            &mut self,
+            to: Option<AccountId>,
+            amount: Balance,
+            token_address: Option<AccountId>,
+            memo: Option<String>,
+            recipient_azero_id: Option<String>,
+            sender_azero_id: Option<String>,
+            expires_at: Option<Timestamp>,
+            unlock_at: Option<Timestamp>,
+            vest_start: Option<Timestamp>,
+            vest_end: Option<Timestamp>,
+            claim_hash: Option<Hash>,
+            approvers: Vec<AccountId>,
+            approval_threshold: u32,
+            arbiter: Option<AccountId>,
+            arbiter_fee: Balance,
+            refund_to: Option<AccountId>,
+            revalidate_azero_id: bool,
+        ) -> Result<Cheque> {
+            let caller: AccountId = Self::env().caller();
+            let params: ChequeParams = ChequeParams {
+                to,
+                amount,
+                token_address,
+                memo,
+                recipient_azero_id,
+                sender_azero_id,
+                expires_at,
+                unlock_at,
+                vest_start,
+                vest_end,
+                claim_hash,
+                period: None,
+                approvers,
+                approval_threshold,
+                arbiter,
+                arbiter_fee,
+                refund_to,
+                revalidate_azero_id,
+            };
 
-        // === HELPERS ===
-        fn admin() -> AccountId {
-            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
-            accounts.alice
+            self.process_creation(caller, params)
         }
 
-        fn mock_azero_id_router_address() -> AccountId {
-            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
-            accounts.django
-        }
+        // Every cheque is validated before anything is transferred, so the whole batch fails
+        // if any one of them is invalid.
+        // Tokens are acquired with a single transfer_from per token.
+        #[ink(message, payable)]
+        pub fn create_batch(&mut self, cheques_params: Vec<ChequeParams>) -> Result<Vec<Cheque>> {
+            self.validate_batch_size(cheques_params.len())?;
+            let caller: AccountId = Self::env().caller();
+            for params in cheques_params.iter() {
+                self.validate_cheque_params(caller, params)?;
+            }
+            if ((u32::MAX - self.cheques_total) as usize) < cheques_params.len() {
+                return Err(AzSafeSendError::RecordsLimitReached("Cheque".to_string()));
+            }
 
-        fn get_balance(account_id: AccountId) -> Balance {
-            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account_id)
-                .expect("Cannot get account balance")
-        }
+            // Total up the AZERO required (fees + AZERO amounts) and the amount of each token
+            let mut azero_required: Balance = 0;
+            let mut fees: Vec<Balance> = vec![];
+            let mut token_amounts: Vec<(AccountId, Balance)> = vec![];
+            for params in cheques_params.iter() {
+                let fee: Balance = self.fee_for(params)?;
+                fees.push(fee);
+                azero_required = azero_required
+                    .checked_add(fee)
+                    .ok_or(AzSafeSendError::IncorrectFee)?;
+                if let Some(token_address_unwrapped) = params.token_address {
+                    if let Some(token_amount) = token_amounts
+                        .iter_mut()
+                        .find(|(token_address, _)| *token_address == token_address_unwrapped)
+                    {
+                        token_amount.1 = token_amount.1.checked_add(params.amount).ok_or(
+                            AzSafeSendError::UnprocessableEntity(
+                                "Amount is too large.".to_string(),
+                            ),
+                        )?;
+                    } else {
+                        token_amounts.push((token_address_unwrapped, params.amount));
+                    }
+                } else {
+                    azero_required = azero_required
+                        .checked_add(params.amount)
+                        .ok_or(AzSafeSendError::IncorrectFee)?;
+                }
+            }
+            if self.env().transferred_value() != azero_required {
+                return Err(AzSafeSendError::IncorrectFee);
+            }
 
-        fn set_balance(account_id: AccountId, balance: Balance) {
-            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(account_id, balance)
+            // Transfer tokens from caller to contract
+            for (token_address, amount) in token_amounts {
+                self.acquire_psp22(token_address, caller, amount)?;
+            }
+
+            Ok(cheques_params
+                .into_iter()
+                .zip(fees)
+                .map(|(params, fee)| self.store_cheque(caller, params, fee, vec![]))
+                .collect())
         }
 
-        fn init() -> (DefaultAccounts<DefaultEnvironment>, AzSafeSend) {
-            let accounts = default_accounts();
-            set_caller::<DefaultEnvironment>(admin());
-            let safe_send = AzSafeSend::new(MOCK_FEE, mock_azero_id_router_address());
-            (accounts, safe_send)
+        // Prefunds a number of instalments of amount, one of which becomes collectable at the
+        // end of each period, starting now.
+        // The fee is charged per instalment.
+        #[allow(clippy::too_many_arguments)]
+        #[ink(message, payable)]
+        pub fn create_recurring(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            token_address: Option<AccountId>,
+            memo: Option<String>,
+            recipient_azero_id: Option<String>,
+            sender_azero_id: Option<String>,
+            period: Timestamp,
+            instalments: u32,
+            refund_to: Option<AccountId>,
+        ) -> Result<Cheque> {
+            if instalments == 0 {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Instalments must be greater than zero.".to_string(),
+                ));
+            }
+            let total_amount: Balance = amount.checked_mul(instalments.into()).ok_or(
+                AzSafeSendError::UnprocessableEntity("Amount is too large.".to_string()),
+            )?;
+            let vest_start: Timestamp = self.env().block_timestamp();
+            let vest_end: Timestamp = period
+                .checked_mul(instalments.into())
+                .and_then(|duration| vest_start.checked_add(duration))
+                .ok_or(AzSafeSendError::UnprocessableEntity(
+                    "Period is too long.".to_string(),
+                ))?;
+            let params: ChequeParams = ChequeParams {
+                to: Some(to),
+                amount: total_amount,
+                token_address,
+                memo,
+                recipient_azero_id,
+                sender_azero_id,
+                expires_at: None,
+                unlock_at: None,
+                vest_start: Some(vest_start),
+                vest_end: Some(vest_end),
+                claim_hash: None,
+                period: Some(period),
+                approvers: vec![],
+                approval_threshold: 0,
+                arbiter: None,
+                arbiter_fee: 0,
+                refund_to,
+                revalidate_azero_id: false,
+            };
+
+            self.process_creation(Self::env().caller(), params)
         }
 
-        fn token_address() -> AccountId {
-            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
-            accounts.charlie
+        // Each recipient collects their own share via collect.
+        // The amount of the cheque is the total of the shares.
+        #[allow(clippy::too_many_arguments)]
+        #[ink(message, payable)]
+        pub fn create_split(
+            &mut self,
+            recipients: Vec<(AccountId, Balance, Option<String>)>,
+            token_address: Option<AccountId>,
+            memo: Option<String>,
+            sender_azero_id: Option<String>,
+            expires_at: Option<Timestamp>,
+            unlock_at: Option<Timestamp>,
+            refund_to: Option<AccountId>,
+        ) -> Result<Cheque> {
+            let caller: AccountId = Self::env().caller();
+            if recipients.is_empty() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Recipients must be provided.".to_string(),
+                ));
+            }
+            if recipients.len() > MAX_RECIPIENTS {
+                return Err(AzSafeSendError::RecordsLimitReached(
+                    "Recipient".to_string(),
+                ));
+            }
+            let mut amount: Balance = 0;
+            for (index, (recipient, share, recipient_azero_id)) in recipients.iter().enumerate() {
+                if *recipient == caller {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Sender and receiver must be different.".to_string(),
+                    ));
+                }
+                if recipients[..index]
+                    .iter()
+                    .any(|(other_recipient, _, _)| other_recipient == recipient)
+                {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Recipients must be unique.".to_string(),
+                    ));
+                }
+                if let Some(recipient_azero_id_unwrapped) = recipient_azero_id.clone() {
+                    self.validate_ownership_of_azero_id(recipient_azero_id_unwrapped, *recipient)?;
+                }
+                if *share == 0 {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Amount must be greater than zero.".to_string(),
+                    ));
+                }
+                amount = amount
+                    .checked_add(*share)
+                    .ok_or(AzSafeSendError::UnprocessableEntity(
+                        "Amount is too large.".to_string(),
+                    ))?;
+            }
+            if let Some(sender_azero_id_unwrapped) = sender_azero_id.clone() {
+                self.validate_ownership_of_azero_id(sender_azero_id_unwrapped, caller)?;
+            }
+            self.validate_refund_to(refund_to)?;
+            self.validate_schedule(expires_at, unlock_at, None, None, None)?;
+            if self.cheques_total == u32::MAX {
+                return Err(AzSafeSendError::RecordsLimitReached("Cheque".to_string()));
+            }
+            self.acquire_payment(caller, token_address, amount, self.fee)?;
+
+            Ok(self.store_cheque(
+                caller,
+                ChequeParams {
+                    to: None,
+                    amount,
+                    token_address,
+                    memo,
+                    recipient_azero_id: None,
+                    sender_azero_id,
+                    expires_at,
+                    unlock_at,
+                    vest_start: None,
+                    vest_end: None,
+                    claim_hash: None,
+                    period: None,
+                    approvers: vec![],
+                    approval_threshold: 0,
+                    arbiter: None,
+                    arbiter_fee: 0,
+                    refund_to,
+                    revalidate_azero_id: false,
+                },
+                self.fee,
+                recipients,
+            ))
         }
 
-        // === TESTS ===
-        #[ink::test]
-        fn test_config() {
-            let (_accounts, az_safe_send) = init();
-            let config = az_safe_send.config();
-            // * it returns the config
-            assert_eq!(config.admin, admin());
-            assert_eq!(config.fee, MOCK_FEE);
+        // Resolves the domain on-chain so that the cheque goes to whoever owns it when created
+        #[ink(message, payable)]
+        pub fn create_to_azero_id(
+            &mut self,
+            domain: String,
+            amount: Balance,
+            token_address: Option<AccountId>,
+            memo: Option<String>,
+            sender_azero_id: Option<String>,
+        ) -> Result<Cheque> {
+            let caller: AccountId = Self::env().caller();
+            let to: AccountId = self.resolve_domain_address(domain.clone())?;
+            let mut params: ChequeParams = ChequeParams {
+                to: Some(to),
+                amount,
+                token_address,
+                memo,
+                recipient_azero_id: None,
+                sender_azero_id,
+                expires_at: None,
+                unlock_at: None,
+                vest_start: None,
+                vest_end: None,
+                claim_hash: None,
+                period: None,
+                approvers: vec![],
+                approval_threshold: 0,
+                arbiter: None,
+                arbiter_fee: 0,
+                refund_to: None,
+                revalidate_azero_id: false,
+            };
+
+            // The domain has just been resolved to to, so it's left out of validation
+            // instead of being resolved again
+            self.validate_cheque_params(caller, &params)?;
+            params.recipient_azero_id = Some(domain);
+
+            self.process_validated_creation(caller, params)
+        }
+
+        // The current recipient hands the cheque over to new_to
+        #[ink(message)]
+        pub fn endorse(
+            &mut self,
+            id: u32,
+            new_to: AccountId,
+            new_to_azero_id: Option<String>,
+        ) -> Result<Cheque> {
+            let mut cheque: Cheque = self.show(id)?;
+            let caller: AccountId = Self::env().caller();
+            self.validate_recipient(&cheque, caller)?;
+            if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            }
+            if new_to == caller || new_to == cheque.from {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "New recipient must be different from sender and recipient.".to_string(),
+                ));
+            }
+            if Some(new_to) == cheque.arbiter {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "New recipient must be different from arbiter.".to_string(),
+                ));
+            }
+            if cheque.endorsements.len() >= MAX_ENDORSEMENTS {
+                return Err(AzSafeSendError::RecordsLimitReached(
+                    "Endorsement".to_string(),
+                ));
+            }
+            if let Some(new_to_azero_id_unwrapped) = new_to_azero_id.clone() {
+                self.validate_ownership_of_azero_id(new_to_azero_id_unwrapped, new_to)?;
+            }
+
+            // Update cheque
+            cheque.endorsements.push(caller);
+            cheque.to = Some(new_to);
+            cheque.recipient_azero_id = new_to_azero_id.clone();
+            self.cheques.insert(cheque.id, &cheque);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::Endorse(Endorse {
+                    id: cheque.id,
+                    from: caller,
+                    to: new_to,
+                    to_azero_id: new_to_azero_id,
+                }),
+            );
+
+            Ok(cheque)
+        }
+
+        // Moves cheques stored by the original release over to the current layout.
+        // They can be read and acted on before then, this frees up the old entries.
+        #[ink(message)]
+        pub fn migrate(&mut self, ids: Vec<u32>) -> Result<()> {
+            if Self::env().caller() != self.admin {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+            self.validate_batch_size(ids.len())?;
+
+            let mut migrated_ids: Vec<u32> = vec![];
+            for id in ids {
+                if let Some(legacy_cheque) = self.legacy_cheques.take(id) {
+                    // Cheques that have been acted on since the upgrade are already stored
+                    // with the current layout
+                    if !self.cheques.contains(id) {
+                        self.cheques.insert(id, &Cheque::from(legacy_cheque));
+                    }
+                    migrated_ids.push(id);
+                }
+            }
+
+            // emit event
+            Self::emit_event(self.env(), Event::Migrate(Migrate { ids: migrated_ids }));
+
+            Ok(())
+        }
+
+        // Freezes the cheque until the arbiter resolves it, or until DISPUTE_TIMEOUT has passed
+        // after which it can be refunded via refund_expired
+        #[ink(message)]
+        pub fn open_dispute(&mut self, id: u32) -> Result<Cheque> {
+            let mut cheque: Cheque = self.show(id)?;
+            let caller: AccountId = Self::env().caller();
+            if caller != cheque.from && Some(caller) != cheque.to {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+            if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            }
+            if cheque.arbiter.is_none() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has no arbiter.".to_string(),
+                ));
+            }
+
+            // Update cheque
+            cheque.status = ChequeStatus::Disputed;
+            cheque.disputed_at = Some(self.env().block_timestamp());
+            self.cheques.insert(cheque.id, &cheque);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::OpenDispute(OpenDispute {
+                    id: cheque.id,
+                    by: caller,
+                }),
+            );
+
+            Ok(cheque)
+        }
+
+        // The sender corrects the recipient before anything has been collected
+        #[ink(message)]
+        pub fn redirect(
+            &mut self,
+            id: u32,
+            new_to: AccountId,
+            recipient_azero_id: Option<String>,
+        ) -> Result<Cheque> {
+            let mut cheque: Cheque = self.show(id)?;
+            let caller: AccountId = Self::env().caller();
+            if caller != cheque.from {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+            if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            }
+            if cheque.collected_amount > 0 || !cheque.endorsements.is_empty() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has already been collected from or endorsed.".to_string(),
+                ));
+            }
+            // Approvals were given for the current recipient
+            if !cheque.approvals.is_empty() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has already been approved.".to_string(),
+                ));
+            }
+            if cheque.is_split() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Split cheques can't be redirected.".to_string(),
+                ));
+            }
+            let old_to: AccountId = if let Some(to) = cheque.to {
+                to
+            } else if cheque.claim_hash.is_some() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Claim links can't be redirected.".to_string(),
+                ));
+            } else {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheques to an unresolved AZERO.ID can't be redirected.".to_string(),
+                ));
+            };
+            if new_to == caller || new_to == old_to {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "New recipient must be different from sender and recipient.".to_string(),
+                ));
+            }
+            if Some(new_to) == cheque.arbiter {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "New recipient must be different from arbiter.".to_string(),
+                ));
+            }
+            if let Some(recipient_azero_id_unwrapped) = recipient_azero_id.clone() {
+                self.validate_ownership_of_azero_id(recipient_azero_id_unwrapped, new_to)?;
+            }
+
+            // Update cheque
+            cheque.to = Some(new_to);
+            cheque.recipient_azero_id = recipient_azero_id.clone();
+            self.cheques.insert(cheque.id, &cheque);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::Redirect(Redirect {
+                    id: cheque.id,
+                    old_to,
+                    new_to,
+                    recipient_azero_id,
+                }),
+            );
+
+            Ok(cheque)
+        }
+
+        // Anyone can return an expired cheque, or one with a dispute that the arbiter hasn't
+        // resolved in time, to its sender
+        #[ink(message)]
+        pub fn refund_expired(&mut self, id: u32) -> Result<Cheque> {
+            let mut cheque: Cheque = self.show(id)?;
+            let block_timestamp: Timestamp = self.env().block_timestamp();
+            if cheque.status == ChequeStatus::Disputed {
+                if !cheque.dispute_has_timed_out(block_timestamp) {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Dispute has not timed out.".to_string(),
+                    ));
+                }
+            } else if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            } else if !cheque.has_expired(block_timestamp) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has not expired.".to_string(),
+                ));
+            }
+
+            self.return_uncollected_to_sender(&cheque)?;
+
+            // Update cheque
+            self.claim_commitments.remove(cheque.id);
+            cheque.status = ChequeStatus::Expired;
+            self.cheques.insert(cheque.id, &cheque);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RefundExpired(RefundExpired { id: cheque.id }),
+            );
+
+            Ok(cheque)
+        }
+
+        #[ink(message)]
+        pub fn reject(&mut self, id: u32, reason: Option<String>) -> Result<Cheque> {
+            let mut cheque: Cheque = self.show(id)?;
+            self.validate_recipient(&cheque, Self::env().caller())?;
+            if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            }
+
+            self.return_uncollected_to_sender(&cheque)?;
+
+            // Update cheque
+            cheque.status = ChequeStatus::Rejected;
+            self.cheques.insert(cheque.id, &cheque);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::Reject(Reject {
+                    id: cheque.id,
+                    reason,
+                }),
+            );
+
+            Ok(cheque)
+        }
+
+        // Domains ending in .tld go back to being resolved by the AZERO.ID router
+        #[ink(message)]
+        pub fn remove_resolver(&mut self, tld: String) -> Result<()> {
+            if Self::env().caller() != self.admin {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+            let tld: String = Self::normalize_tld(tld)?;
+            self.resolver(tld.clone())?;
+
+            self.resolvers.remove(tld.clone());
+
+            // emit event
+            Self::emit_event(self.env(), Event::RemoveResolver(RemoveResolver { tld }));
+
+            Ok(())
+        }
+
+        // The arbiter splits the uncollected amount, less their fee, between the parties.
+        // The fee is paid to the admin if the recipient is awarded anything, otherwise it's
+        // returned to the sender.
+        #[ink(message)]
+        pub fn resolve(
+            &mut self,
+            id: u32,
+            to_recipient: Balance,
+            to_sender: Balance,
+        ) -> Result<Cheque> {
+            let mut cheque: Cheque = self.show(id)?;
+            if Some(Self::env().caller()) != cheque.arbiter {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+            if cheque.status != ChequeStatus::Disputed {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be disputed.".to_string(),
+                ));
+            }
+            // Timed out disputes are left to refund_expired
+            if cheque.dispute_has_timed_out(self.env().block_timestamp()) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Dispute has timed out.".to_string(),
+                ));
+            }
+            let uncollected_amount: Balance = cheque.amount - cheque.collected_amount;
+            let arbiter_fee: Balance = cheque.arbiter_fee.min(uncollected_amount);
+            if to_recipient.checked_add(to_sender) != Some(uncollected_amount - arbiter_fee) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Ruling must split the uncollected amount less the arbiter fee.".to_string(),
+                ));
+            }
+            let recipient: AccountId = cheque.to.ok_or(AzSafeSendError::Unauthorised)?;
+            let fee_to_admin: Balance = if to_recipient > 0 {
+                cheque.fee_due(to_recipient)
+            } else {
+                0
+            };
+            let fee_to_return: Balance =
+                cheque.unearned_fee(cheque.collected_amount + to_recipient);
+
+            // Update cheque
+            cheque.collected_amount += to_recipient;
+            if to_recipient > 0 {
+                cheque.beneficiary = Some(recipient);
+            }
+            cheque.status = ChequeStatus::Resolved;
+            self.cheques.insert(cheque.id, &cheque);
+
+            // Transfer ruling and fees
+            self.transfer(cheque.token_address, recipient, to_recipient)?;
+            self.return_to_sender(&cheque, to_sender, fee_to_return)?;
+            if let Some(arbiter) = cheque.arbiter {
+                self.transfer(cheque.token_address, arbiter, arbiter_fee)?;
+            }
+            self.transfer_azero(self.admin, fee_to_admin);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::Resolve(Resolve {
+                    id: cheque.id,
+                    to_recipient,
+                    to_sender,
+                    arbiter_fee,
+                }),
+            );
+
+            Ok(cheque)
+        }
+
+        // Approvals can be revoked until the recipient starts collecting
+        #[ink(message)]
+        pub fn revoke_approval(&mut self, id: u32) -> Result<Cheque> {
+            let mut cheque: Cheque = self.show(id)?;
+            let caller: AccountId = Self::env().caller();
+            if !cheque.approvers.contains(&caller) {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+            if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            }
+            // Once the threshold has been met the cheque is committed to the recipient
+            if cheque.is_approved() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has been approved.".to_string(),
+                ));
+            }
+            if !cheque.approvals.contains(&caller) {
+                return Err(AzSafeSendError::NotFound("Approval".to_string()));
+            }
+
+            // Update cheque
+            cheque.approvals.retain(|approver| *approver != caller);
+            self.cheques.insert(cheque.id, &cheque);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::RevokeApproval(RevokeApproval {
+                    id: cheque.id,
+                    approver: caller,
+                }),
+            );
+
+            Ok(cheque)
+        }
+
+        // Upgrades the contract in place. Upgrades that change how anything is stored
+        // need a migration like migrate.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<()> {
+            if Self::env().caller() != self.admin {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+
+            self.env().set_code_hash(&code_hash)?;
+
+            // emit event
+            Self::emit_event(self.env(), Event::SetCode(SetCode { code_hash }));
+
+            Ok(())
+        }
+
+        #[ink(message, payable)]
+        pub fn top_up(&mut self, id: u32, extra_amount: Balance) -> Result<Cheque> {
+            let mut cheque: Cheque = self.show(id)?;
+            let caller: AccountId = Self::env().caller();
+            if caller != cheque.from {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+            if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            }
+            if cheque.is_split() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Split cheques can't be topped up.".to_string(),
+                ));
+            }
+            if cheque.period.is_some() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Recurring cheques can't be topped up.".to_string(),
+                ));
+            }
+            // The extra amount would count as having vested since vest_start
+            if cheque.is_vesting() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Vesting cheques can't be topped up.".to_string(),
+                ));
+            }
+            // Neither the holder of the secret nor the approvers agreed to the extra amount
+            if cheque.claim_hash.is_some() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Claim links can't be topped up.".to_string(),
+                ));
+            }
+            if !cheque.approvers.is_empty() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Approver gated cheques can't be topped up.".to_string(),
+                ));
+            }
+            // Anything added now could only be refunded
+            if cheque.has_expired(self.env().block_timestamp()) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has expired.".to_string(),
+                ));
+            }
+            if extra_amount == 0 {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount must be greater than zero.".to_string(),
+                ));
+            }
+            if let Some(amount) = cheque.amount.checked_add(extra_amount) {
+                cheque.amount = amount
+            } else {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount is too large.".to_string(),
+                ));
+            }
+            if let Some(token_address_unwrapped) = cheque.token_address {
+                // Check no AZERO is sent in if token
+                if self.env().transferred_value() != 0 {
+                    return Err(AzSafeSendError::IncorrectFee);
+                }
+
+                // Transfer token from caller to contract
+                self.acquire_psp22(token_address_unwrapped, caller, extra_amount)?;
+            } else if self.env().transferred_value() != extra_amount {
+                // Check AZERO sent in equals extra amount if no token_address
+                return Err(AzSafeSendError::IncorrectFee);
+            }
+
+            // Update cheque
+            self.cheques.insert(cheque.id, &cheque);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::TopUp(TopUp {
+                    id: cheque.id,
+                    extra_amount,
+                    amount: cheque.amount,
+                }),
+            );
+
+            Ok(cheque)
+        }
+
+        // The new router must respond to get_address for AZERO_ID_PROBE_DOMAIN before it's
+        // accepted
+        #[ink(message)]
+        pub fn update_azero_id_router_address(
+            &mut self,
+            azero_id_router_address: AccountId,
+        ) -> Result<()> {
+            if Self::env().caller() != self.admin {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+            // Any response will do, whether or not the domain exists
+            let _ = self.resolve_domain(
+                &Self::azero_id_router(azero_id_router_address),
+                AZERO_ID_PROBE_DOMAIN.to_string(),
+            )?;
+
+            self.azero_id_router_address = azero_id_router_address;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::UpdateAzeroIdRouterAddress(UpdateAzeroIdRouterAddress {
+                    azero_id_router_address,
+                }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_fee(&mut self, fee: Balance) -> Result<()> {
+            if Self::env().caller() != self.admin {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+
+            self.fee = fee;
+
+            // emit event
+            Self::emit_event(self.env(), Event::UpdateFee(UpdateFee { fee }));
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn update_memo(&mut self, id: u32, memo: Option<String>) -> Result<Cheque> {
+            let mut cheque: Cheque = self.show(id)?;
+            if Self::env().caller() != cheque.from {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+            if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            }
+
+            // Update cheque
+            cheque.memo = memo;
+            self.cheques.insert(cheque.id, &cheque);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::UpdateMemo(UpdateMemo {
+                    id: cheque.id,
+                    memo: cheque.memo.clone(),
+                }),
+            );
+
+            Ok(cheque)
+        }
+
+        // === PRIVATE ===
+        // Takes the fee, plus the amount if no token address, in AZERO and the amount in the
+        // token if there's a token address
+        fn acquire_payment(
+            &self,
+            caller: AccountId,
+            token_address: Option<AccountId>,
+            amount: Balance,
+            fee: Balance,
+        ) -> Result<()> {
+            if let Some(token_address_unwrapped) = token_address {
+                // Check AZERO sent in equals fee if token
+                if self.env().transferred_value() != fee {
+                    return Err(AzSafeSendError::IncorrectFee);
+                }
+
+                // Transfer token from caller to contract
+                self.acquire_psp22(token_address_unwrapped, caller, amount)?;
+            } else {
+                // Check AZERO sent in equals fee + amount if no token_address
+                if fee.checked_add(amount).is_none()
+                    || self.env().transferred_value() != fee + amount
+                {
+                    return Err(AzSafeSendError::IncorrectFee);
+                }
+            }
+
+            Ok(())
+        }
+
+        fn acquire_psp22(&self, token: AccountId, from: AccountId, amount: Balance) -> Result<()> {
+            PSP22Ref::transfer_from_builder(&token, from, self.env().account_id(), amount, vec![])
+                .call_flags(CallFlags::default())
+                .invoke()?;
+
+            Ok(())
+        }
+
+        // Adds amount to the running total owed to to in token_address, where None is AZERO
+        fn add_to_payouts(
+            payouts: &mut Vec<(AccountId, Option<AccountId>, Balance)>,
+            to: AccountId,
+            token_address: Option<AccountId>,
+            amount: Balance,
+        ) -> Result<()> {
+            if let Some(payout) = payouts
+                .iter_mut()
+                .find(|(payout_to, payout_token_address, _)| {
+                    *payout_to == to && *payout_token_address == token_address
+                })
+            {
+                payout.2 =
+                    payout
+                        .2
+                        .checked_add(amount)
+                        .ok_or(AzSafeSendError::UnprocessableEntity(
+                            "Amount is too large.".to_string(),
+                        ))?;
+            } else {
+                payouts.push((to, token_address, amount));
+            }
+
+            Ok(())
+        }
+
+        fn azero_id_router(azero_id_router_address: AccountId) -> Resolver {
+            Resolver {
+                address: azero_id_router_address,
+                selector: AZERO_ID_GET_ADDRESS_SELECTOR,
+                return_type: ResolverReturnType::AccountIdResult,
+            }
+        }
+
+        // See smart contract hub contract for testing
+        fn call_resolver<R: scale::Decode>(
+            &self,
+            resolver: &Resolver,
+            domain: String,
+        ) -> Result<R> {
+            let result = build_call::<Environment>()
+                .call(resolver.address)
+                .exec_input(ExecutionInput::new(Selector::new(resolver.selector)).push_arg(domain))
+                .returns::<R>()
+                .params()
+                .try_invoke()??;
+
+            Ok(result)
+        }
+
+        fn collect_signature_message_hash(
+            &self,
+            cheque: &Cheque,
+            beneficiary: AccountId,
+            deadline: Timestamp,
+            tip: Balance,
+        ) -> Result<[u8; 32]> {
+            let to: AccountId = cheque.to.ok_or(AzSafeSendError::Unauthorised)?;
+
+            Ok(self.env().hash_encoded::<Blake2x256, _>(&(
+                COLLECT_WITH_SIGNATURE_DOMAIN,
+                self.env().account_id(),
+                cheque.id,
+                beneficiary,
+                deadline,
+                tip,
+                self.nonce(to),
+            )))
+        }
+
+        fn emit_event<EE: EmitEvent<Self>>(emitter: EE, event: Event) {
+            emitter.emit_event(event);
+        }
+
+        // Snapshot of the fee, charged per instalment for recurring cheques
+        fn fee_for(&self, params: &ChequeParams) -> Result<Balance> {
+            if let (Some(period), Some(vest_start), Some(vest_end)) =
+                (params.period, params.vest_start, params.vest_end)
+            {
+                let instalments: Balance = ((vest_end - vest_start) / period).into();
+                self.fee
+                    .checked_mul(instalments)
+                    .ok_or(AzSafeSendError::IncorrectFee)
+            } else {
+                Ok(self.fee)
+            }
+        }
+
+        // TLDs are stored in lowercase without a leading dot, e.g. .AZERO as azero
+        fn normalize_tld(tld: String) -> Result<String> {
+            let tld: &str = tld.strip_prefix('.').unwrap_or(&tld);
+            if tld.is_empty() || tld.contains('.') {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "TLD is invalid.".to_string(),
+                ));
+            }
+
+            Ok(tld.to_ascii_lowercase())
+        }
+
+        fn pay_out(&self, payouts: Vec<(AccountId, Option<AccountId>, Balance)>) -> Result<()> {
+            for (to, token_address, amount) in payouts {
+                self.transfer(token_address, to, amount)?;
+            }
+
+            Ok(())
+        }
+
+        // Sends the requested amount, or everything collectable if no amount is given,
+        // to the beneficiary.
+        // The fee is transferred to the admin on the first collection.
+        fn process_collection(
+            &mut self,
+            cheque: Cheque,
+            beneficiary: AccountId,
+            amount: Option<Balance>,
+        ) -> Result<Cheque> {
+            let (cheque, amount, fee) = self.record_collection(cheque, beneficiary, amount)?;
+            self.transfer(cheque.token_address, beneficiary, amount)?;
+            self.transfer_azero(self.admin, fee);
+
+            Ok(cheque)
+        }
+
+        fn process_creation(&mut self, caller: AccountId, params: ChequeParams) -> Result<Cheque> {
+            self.validate_cheque_params(caller, &params)?;
+
+            self.process_validated_creation(caller, params)
+        }
+
+        fn process_validated_creation(
+            &mut self,
+            caller: AccountId,
+            params: ChequeParams,
+        ) -> Result<Cheque> {
+            if self.cheques_total == u32::MAX {
+                return Err(AzSafeSendError::RecordsLimitReached("Cheque".to_string()));
+            }
+            let fee: Balance = self.fee_for(&params)?;
+            self.acquire_payment(caller, params.token_address, params.amount, fee)?;
+
+            Ok(self.store_cheque(caller, params, fee, vec![]))
+        }
+
+        // Updates the cheque as cancelled without transferring anything.
+        // Returns the amount and fee to be returned to the sender.
+        fn record_cancellation(
+            &mut self,
+            mut cheque: Cheque,
+        ) -> Result<(Cheque, Balance, Balance)> {
+            if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            }
+            if !cheque.approvers.is_empty() && cheque.is_approved() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has been approved.".to_string(),
+                ));
+            }
+
+            // Vested funds stay collectable by the recipient
+            let block_timestamp: Timestamp = self.env().block_timestamp();
+            let retained_amount: Balance = if cheque.is_vesting() {
+                cheque.vested_amount(block_timestamp)
+            } else {
+                cheque.collected_amount
+            };
+            if retained_amount == cheque.amount {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque is fully vested.".to_string(),
+                ));
+            }
+            let amount_to_return: Balance = cheque.amount - retained_amount;
+            let fee_to_return: Balance = cheque.unearned_fee(retained_amount);
+
+            // Update cheque
+            if retained_amount > cheque.collected_amount {
+                // Recurring cheques end with the last instalment that has become due
+                cheque.vest_end =
+                    if let (Some(period), Some(vest_start)) = (cheque.period, cheque.vest_start) {
+                        let instalments_retained: Balance =
+                            retained_amount / (cheque.amount / cheque.instalments());
+                        Some(vest_start + period * instalments_retained as Timestamp)
+                    } else {
+                        Some(block_timestamp)
+                    };
+                cheque.amount = retained_amount;
+                cheque.fee -= fee_to_return;
+            } else {
+                cheque.status = ChequeStatus::Cancelled;
+                self.claim_commitments.remove(cheque.id);
+            }
+            self.cheques.insert(cheque.id, &cheque);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::Cancel(Cancel {
+                    id: cheque.id,
+                    recipient_azero_id: cheque.recipient_azero_id.clone(),
+                    sender_azero_id: cheque.sender_azero_id.clone(),
+                }),
+            );
+
+            Ok((cheque, amount_to_return, fee_to_return))
+        }
+
+        // Updates the cheque with a collection without transferring anything.
+        // Returns the amount collected and the fee due to the admin, which is only due
+        // on the first collection.
+        fn record_collection(
+            &mut self,
+            mut cheque: Cheque,
+            beneficiary: AccountId,
+            amount: Option<Balance>,
+        ) -> Result<(Cheque, Balance, Balance)> {
+            if cheque.status != ChequeStatus::PendingCollection {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string(),
+                ));
+            }
+            let block_timestamp: Timestamp = self.env().block_timestamp();
+            if cheque.has_expired(block_timestamp) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has expired.".to_string(),
+                ));
+            }
+            if cheque.is_locked(block_timestamp) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque is locked.".to_string(),
+                ));
+            }
+            if cheque.revalidate_azero_id {
+                if let (Some(to), Some(recipient_azero_id)) =
+                    (cheque.to, cheque.recipient_azero_id.clone())
+                {
+                    self.validate_ownership_of_azero_id(recipient_azero_id, to)?;
+                }
+            }
+            if !cheque.is_approved() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has not been approved.".to_string(),
+                ));
+            }
+            let collectable_amount: Balance =
+                cheque.vested_amount(block_timestamp) - cheque.collected_amount;
+            if collectable_amount == 0 {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Nothing to collect.".to_string(),
+                ));
+            }
+            let amount: Balance = amount.unwrap_or(collectable_amount);
+            if amount == 0 {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount must be greater than zero.".to_string(),
+                ));
+            }
+            if amount > collectable_amount {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount must be less than or equal to collectable amount.".to_string(),
+                ));
+            }
+
+            let fee: Balance = cheque.fee_due(amount);
+
+            // set status
+            cheque.collected_amount += amount;
+            cheque.beneficiary = Some(beneficiary);
+            if cheque.collected_amount == cheque.amount {
+                cheque.status = ChequeStatus::Collected;
+                self.claim_commitments.remove(cheque.id);
+            }
+            self.cheques.insert(cheque.id, &cheque);
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::Collect(Collect {
+                    id: cheque.id,
+                    amount,
+                    beneficiary,
+                    recipient_azero_id: cheque.recipient_azero_id.clone(),
+                    sender_azero_id: cheque.sender_azero_id.clone(),
+                }),
+            );
+
+            Ok((cheque, amount, fee))
+        }
+
+        // Collects everything collectable by the recipient, or their share if the cheque is split
+        fn record_recipient_collection(
+            &mut self,
+            mut cheque: Cheque,
+            recipient: AccountId,
+        ) -> Result<(Cheque, Balance, Balance)> {
+            if cheque.is_split() {
+                let share: Balance = if let Some((_, share)) = cheque
+                    .recipients
+                    .iter()
+                    .find(|(split_recipient, _)| *split_recipient == recipient)
+                {
+                    *share
+                } else {
+                    return Err(AzSafeSendError::Unauthorised);
+                };
+                if cheque.collected_recipients.contains(&recipient) {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Share has already been collected.".to_string(),
+                    ));
+                }
+                cheque.collected_recipients.push(recipient);
+
+                self.record_collection(cheque, recipient, Some(share))
+            } else {
+                self.validate_recipient(&cheque, recipient)?;
+
+                self.record_collection(cheque, recipient, None)
+            }
+        }
+
+        // None if the domain doesn't resolve to an address
+        fn resolve_domain(&self, resolver: &Resolver, domain: String) -> Result<Option<AccountId>> {
+            match resolver.return_type {
+                ResolverReturnType::AccountIdResult => Ok(self
+                    .call_resolver::<core::result::Result<AccountId, u8>>(resolver, domain)?
+                    .ok()),
+                ResolverReturnType::OptionalAccountId => {
+                    self.call_resolver::<Option<AccountId>>(resolver, domain)
+                }
+                ResolverReturnType::AccountId => {
+                    Ok(Some(self.call_resolver::<AccountId>(resolver, domain)?))
+                }
+            }
+        }
+
+        // Resolves the domain with the resolver registered for its TLD
+        fn resolve_domain_address(&self, domain: String) -> Result<AccountId> {
+            let resolver: Resolver = self.resolver_for(&domain);
+            self.resolve_domain(&resolver, domain)?
+                .ok_or(AzSafeSendError::NotFound("Domain".to_string()))
+        }
+
+        // Domains without a TLD are treated as their own TLD.
+        // TLDs are case insensitive.
+        fn resolver_for(&self, domain: &str) -> Resolver {
+            let tld: &str = domain.rsplit('.').next().unwrap_or(domain);
+            self.resolvers
+                .get(tld.to_ascii_lowercase())
+                .unwrap_or(Self::azero_id_router(self.azero_id_router_address))
+        }
+
+        // Refunds go to the cheque's refund address if it has one
+        fn return_to_sender(&self, cheque: &Cheque, amount: Balance, fee: Balance) -> Result<()> {
+            let refund_to: AccountId = cheque.refund_address();
+            let mut azero_to_return_to_user: Balance = 0;
+            // Return amount to sender
+            if cheque.token_address.is_some() {
+                self.transfer(cheque.token_address, refund_to, amount)?;
+            } else {
+                azero_to_return_to_user += amount
+            }
+
+            // Return fee to sender
+            azero_to_return_to_user += fee;
+            self.transfer_azero(refund_to, azero_to_return_to_user);
+
+            Ok(())
+        }
+
+        // The fee is only returned if the recipient hasn't collected anything, or for each
+        // uncollected instalment of a recurring cheque
+        fn return_uncollected_to_sender(&self, cheque: &Cheque) -> Result<()> {
+            self.return_to_sender(
+                cheque,
+                cheque.amount - cheque.collected_amount,
+                cheque.unearned_fee(cheque.collected_amount),
+            )
+        }
+
+        // Assumes the params and recipients have been validated and paid for
+        fn store_cheque(
+            &mut self,
+            from: AccountId,
+            params: ChequeParams,
+            fee: Balance,
+            recipients: Vec<(AccountId, Balance, Option<String>)>,
+        ) -> Cheque {
+            let cheque: Cheque = Cheque {
+                id: self.cheques_total,
+                from,
+                to: params.to,
+                amount: params.amount,
+                token_address: params.token_address,
+                status: ChequeStatus::PendingCollection,
+                fee,
+                memo: params.memo,
+                expires_at: params.expires_at,
+                unlock_at: params.unlock_at,
+                vest_start: params.vest_start,
+                vest_end: params.vest_end,
+                collected_amount: 0,
+                beneficiary: None,
+                endorsements: vec![],
+                claim_hash: params.claim_hash,
+                recipients: recipients
+                    .iter()
+                    .map(|(recipient, share, _)| (*recipient, *share))
+                    .collect(),
+                collected_recipients: vec![],
+                period: params.period,
+                approvers: params.approvers.clone(),
+                approval_threshold: params.approval_threshold,
+                approvals: vec![],
+                arbiter: params.arbiter,
+                arbiter_fee: params.arbiter_fee,
+                disputed_at: None,
+                refund_to: params.refund_to,
+                recipient_azero_id: params.recipient_azero_id,
+                revalidate_azero_id: params.revalidate_azero_id,
+                sender_azero_id: params.sender_azero_id,
+            };
+            self.cheques.insert(self.cheques_total, &cheque);
+            self.cheques_total += 1;
+
+            // emit event
+            Self::emit_event(
+                self.env(),
+                Event::Create(Create {
+                    id: cheque.id,
+                    from: cheque.from,
+                    to: cheque.to,
+                    amount: cheque.amount,
+                    token_address: cheque.token_address,
+                    fee: cheque.fee,
+                    memo: cheque.memo.clone(),
+                    recipient_azero_id: cheque.recipient_azero_id.clone(),
+                    sender_azero_id: cheque.sender_azero_id.clone(),
+                    expires_at: cheque.expires_at,
+                    unlock_at: cheque.unlock_at,
+                    vest_start: cheque.vest_start,
+                    vest_end: cheque.vest_end,
+                    claim_hash: cheque.claim_hash,
+                    recipients,
+                    period: cheque.period,
+                    approvers: params.approvers,
+                    approval_threshold: cheque.approval_threshold,
+                    arbiter: cheque.arbiter,
+                    arbiter_fee: cheque.arbiter_fee,
+                    refund_to: cheque.refund_to,
+                    revalidate_azero_id: cheque.revalidate_azero_id,
+                }),
+            );
+
+            cheque
+        }
+
+        fn transfer(
+            &self,
+            token_address: Option<AccountId>,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            if let Some(token_address_unwrapped) = token_address {
+                if amount > 0 {
+                    PSP22Ref::transfer_builder(&token_address_unwrapped, to, amount, vec![])
+                        .call_flags(CallFlags::default())
+                        .invoke()?;
+                }
+            } else {
+                self.transfer_azero(to, amount);
+            }
+
+            Ok(())
+        }
+
+        fn transfer_azero(&self, to: AccountId, amount: Balance) {
+            if amount > 0 && self.env().transfer(to, amount).is_err() {
+                panic!(
+                    "requested transfer failed. this can be the case if the contract does not\
+                         have sufficient free funds or if the transfer would have brought the\
+                         contract's balance below minimum balance."
+                )
+            }
+        }
+
+        fn validate_batch_size(&self, size: usize) -> Result<()> {
+            if size == 0 {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Batch must not be empty.".to_string(),
+                ));
+            }
+            if size > MAX_BATCH_SIZE {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Batch is too large.".to_string(),
+                ));
+            }
+
+            Ok(())
+        }
+
+        // Anything paid to the contract itself would be stranded
+        fn validate_beneficiary(&self, beneficiary: AccountId) -> Result<()> {
+            if beneficiary == self.env().account_id() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Beneficiary must not be this contract.".to_string(),
+                ));
+            }
+
+            Ok(())
+        }
+
+        fn validate_cheque_params(&self, caller: AccountId, params: &ChequeParams) -> Result<()> {
+            // A receiver AZERO.ID without a receiver is resolved on collection instead
+            let has_receiver: bool = params.to.is_some() || params.recipient_azero_id.is_some();
+            if has_receiver == params.claim_hash.is_some() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Either a receiver or a claim hash must be provided.".to_string(),
+                ));
+            }
+            if params.to == Some(caller) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Sender and receiver must be different.".to_string(),
+                ));
+            }
+            if let (Some(recipient_azero_id_unwrapped), Some(to_unwrapped)) =
+                (params.recipient_azero_id.clone(), params.to)
+            {
+                self.validate_ownership_of_azero_id(recipient_azero_id_unwrapped, to_unwrapped)?;
+            }
+            // So that the sender can get the funds back if the domain never resolves
+            if params.to.is_none()
+                && params.recipient_azero_id.is_some()
+                && params.expires_at.is_none()
+            {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Expiry is required when the receiver is an AZERO.ID only.".to_string(),
+                ));
+            }
+            if params.revalidate_azero_id && params.recipient_azero_id.is_none() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "AZERO.ID revalidation requires a receiver AZERO.ID.".to_string(),
+                ));
+            }
+            if let Some(sender_azero_id_unwrapped) = params.sender_azero_id.clone() {
+                self.validate_ownership_of_azero_id(sender_azero_id_unwrapped, caller)?;
+            }
+            self.validate_refund_to(params.refund_to)?;
+            if params.amount == 0 {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount must be greater than zero.".to_string(),
+                ));
+            }
+            if params.approvers.len() > MAX_APPROVERS {
+                return Err(AzSafeSendError::RecordsLimitReached("Approver".to_string()));
+            }
+            for (index, approver) in params.approvers.iter().enumerate() {
+                if params.approvers[..index].contains(approver) {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Approvers must be unique.".to_string(),
+                    ));
+                }
+            }
+            if params.approval_threshold as usize > params.approvers.len()
+                || (params.approval_threshold == 0) != params.approvers.is_empty()
+            {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Approval threshold must be between one and the number of approvers."
+                        .to_string(),
+                ));
+            }
+            if let Some(arbiter) = params.arbiter {
+                if params.to.is_none() {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Arbiter requires a receiver.".to_string(),
+                    ));
+                }
+                if arbiter == caller || Some(arbiter) == params.to {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Arbiter must be different from sender and receiver.".to_string(),
+                    ));
+                }
+                if params.arbiter_fee >= params.amount {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Arbiter fee must be less than amount.".to_string(),
+                    ));
+                }
+            } else if params.arbiter_fee > 0 {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Arbiter fee requires an arbiter.".to_string(),
+                ));
+            }
+            self.validate_schedule(
+                params.expires_at,
+                params.unlock_at,
+                params.vest_start,
+                params.vest_end,
+                params.period,
+            )?;
+            if let (Some(period), Some(vest_start), Some(vest_end)) =
+                (params.period, params.vest_start, params.vest_end)
+            {
+                let instalments: Balance = ((vest_end - vest_start) / period).into();
+                let remainder: Balance = params.amount % instalments;
+                if remainder != 0 {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Amount must be divisible by the number of instalments.".to_string(),
+                    ));
+                }
+            }
+
+            Ok(())
+        }
+
+        fn validate_recipient(&self, cheque: &Cheque, recipient: AccountId) -> Result<()> {
+            let is_recipient: bool = match (cheque.to, cheque.recipient_azero_id.clone()) {
+                (Some(to), _) => to == recipient,
+                (None, Some(recipient_azero_id)) => {
+                    self.resolve_domain_address(recipient_azero_id)? == recipient
+                }
+                (None, None) => false,
+            };
+            if !is_recipient {
+                return Err(AzSafeSendError::Unauthorised);
+            }
+
+            Ok(())
+        }
+
+        fn validate_refund_to(&self, refund_to: Option<AccountId>) -> Result<()> {
+            if refund_to == Some(self.env().account_id()) {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Refund address must not be this contract.".to_string(),
+                ));
+            }
+
+            Ok(())
+        }
+
+        fn validate_schedule(
+            &self,
+            expires_at: Option<Timestamp>,
+            unlock_at: Option<Timestamp>,
+            vest_start: Option<Timestamp>,
+            vest_end: Option<Timestamp>,
+            period: Option<Timestamp>,
+        ) -> Result<()> {
+            if let Some(period_unwrapped) = period {
+                if period_unwrapped == 0 {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Period must be greater than zero.".to_string(),
+                    ));
+                }
+                if vest_start.is_none() || vest_end.is_none() {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Period requires vesting start and end.".to_string(),
+                    ));
+                }
+            }
+            if let Some(expires_at_unwrapped) = expires_at {
+                if expires_at_unwrapped <= self.env().block_timestamp() {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Expiry must be in the future.".to_string(),
+                    ));
+                }
+                if let Some(unlock_at_unwrapped) = unlock_at {
+                    if unlock_at_unwrapped >= expires_at_unwrapped {
+                        return Err(AzSafeSendError::UnprocessableEntity(
+                            "Unlock must be before expiry.".to_string(),
+                        ));
+                    }
+                }
+            }
+            if vest_start.is_some() != vest_end.is_some() {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Vesting start and end must be provided together.".to_string(),
+                ));
+            }
+            if let (Some(vest_start_unwrapped), Some(vest_end_unwrapped)) = (vest_start, vest_end) {
+                if vest_end_unwrapped <= vest_start_unwrapped {
+                    return Err(AzSafeSendError::UnprocessableEntity(
+                        "Vesting end must be after vesting start.".to_string(),
+                    ));
+                }
+                if let Some(expires_at_unwrapped) = expires_at {
+                    if expires_at_unwrapped <= vest_end_unwrapped {
+                        return Err(AzSafeSendError::UnprocessableEntity(
+                            "Expiry must be after vesting end.".to_string(),
+                        ));
+                    }
+                }
+                if let Some(period_unwrapped) = period {
+                    let remainder: Timestamp =
+                        (vest_end_unwrapped - vest_start_unwrapped) % period_unwrapped;
+                    if remainder != 0 {
+                        return Err(AzSafeSendError::UnprocessableEntity(
+                            "Vesting duration must be a multiple of period.".to_string(),
+                        ));
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        fn validate_ownership_of_azero_id(
+            &self,
+            azero_id: String,
+            address: AccountId,
+        ) -> Result<()> {
+            if address != self.resolve_domain_address(azero_id)? {
+                return Err(AzSafeSendError::UnprocessableEntity(
+                    "Domain does not belong to address".to_string(),
+                ));
+            }
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{
+            test::{default_accounts, set_block_timestamp, set_caller, DefaultAccounts},
+            DefaultEnvironment,
+        };
+        use secp256k1::{Message, PublicKey, SecretKey, SECP256K1};
+
+        // === CONSTANTS ===
+        const MOCK_AMOUNT: Balance = 250;
+        const MOCK_FEE: Balance = 500;
+
+        // === HELPERS ===
+        fn admin() -> AccountId {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            accounts.alice
+        }
+
+        fn mock_azero_id_router_address() -> AccountId {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            accounts.django
+        }
+
+        fn get_balance(account_id: AccountId) -> Balance {
+            ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(account_id)
+                .expect("Cannot get account balance")
+        }
+
+        fn set_balance(account_id: AccountId, balance: Balance) {
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(account_id, balance)
+        }
+
+        fn init() -> (DefaultAccounts<DefaultEnvironment>, AzSafeSend) {
+            let accounts = default_accounts();
+            set_caller::<DefaultEnvironment>(admin());
+            let safe_send = AzSafeSend::new(MOCK_FEE, mock_azero_id_router_address());
+            (accounts, safe_send)
+        }
+
+        fn last_event() -> Event {
+            let event = ink::env::test::recorded_events().last().unwrap();
+            <Event as scale::Decode>::decode(&mut &event.data[..]).unwrap()
+        }
+
+        fn token_address() -> AccountId {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            accounts.charlie
+        }
+
+        fn claim_hash(secret: &[u8]) -> Hash {
+            let mut output = <Blake2x256 as ink::env::hash::HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(secret, &mut output);
+            Hash::from(output)
+        }
+
+        fn ecdsa_account_id(secret_key: &SecretKey) -> AccountId {
+            let public_key: [u8; 33] =
+                PublicKey::from_secret_key(SECP256K1, secret_key).serialize();
+            let mut output = <Blake2x256 as ink::env::hash::HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&public_key, &mut output);
+            AccountId::from(output)
+        }
+
+        fn ecdsa_sign(secret_key: &SecretKey, message_hash: Hash) -> [u8; 65] {
+            let message: Message = Message::from_slice(message_hash.as_ref()).unwrap();
+            let (recovery_id, compact) = SECP256K1
+                .sign_ecdsa_recoverable(&message, secret_key)
+                .serialize_compact();
+            let mut signature: [u8; 65] = [0; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+            signature
+        }
+
+        fn cheque_params(to: Option<AccountId>, amount: Balance) -> ChequeParams {
+            ChequeParams {
+                to,
+                amount,
+                token_address: None,
+                memo: None,
+                recipient_azero_id: None,
+                sender_azero_id: None,
+                expires_at: None,
+                unlock_at: None,
+                vest_start: None,
+                vest_end: None,
+                claim_hash: None,
+                period: None,
+                approvers: vec![],
+                approval_threshold: 0,
+                arbiter: None,
+                arbiter_fee: 0,
+                refund_to: None,
+                revalidate_azero_id: false,
+            }
+        }
+
+        // Two of charlie, django and eve must approve
+        fn create_approver_gated(
+            az_safe_send: &mut AzSafeSend,
+            accounts: &DefaultAccounts<DefaultEnvironment>,
+        ) -> Cheque {
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![accounts.charlie, accounts.django, accounts.eve],
+                    2,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap()
+        }
+
+        // Django sends to bob with charlie arbitrating for a fee of 50
+        fn create_arbitrated(
+            az_safe_send: &mut AzSafeSend,
+            accounts: &DefaultAccounts<DefaultEnvironment>,
+        ) -> Cheque {
+            set_caller::<DefaultEnvironment>(accounts.django);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    Some(accounts.charlie),
+                    50,
+                    None,
+                    false,
+                )
+                .unwrap()
+        }
+
+        fn create_claim_link(az_safe_send: &mut AzSafeSend, secret: &[u8]) -> Cheque {
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            az_safe_send
+                .create(
+                    None,
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(claim_hash(secret)),
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap()
+        }
+
+        // === TESTS ===
+        #[ink::test]
+        fn test_add_to_payouts() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let mut payouts: Vec<(AccountId, Option<AccountId>, Balance)> = vec![];
+            // when payout to the same account and token exists
+            AzSafeSend::add_to_payouts(&mut payouts, accounts.bob, None, MOCK_AMOUNT).unwrap();
+            AzSafeSend::add_to_payouts(&mut payouts, accounts.bob, None, MOCK_AMOUNT).unwrap();
+            AzSafeSend::add_to_payouts(&mut payouts, accounts.bob, Some(token_address()), 1)
+                .unwrap();
+            // * it adds to the existing payout
+            assert_eq!(
+                payouts,
+                vec![
+                    (accounts.bob, None, MOCK_AMOUNT * 2),
+                    (accounts.bob, Some(token_address()), 1)
+                ]
+            );
+            // = when the total would overflow
+            // = * it raises an error
+            let result = AzSafeSend::add_to_payouts(&mut payouts, accounts.bob, None, Balance::MAX);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount is too large.".to_string()
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn test_config() {
+            let (_accounts, az_safe_send) = init();
+            let config = az_safe_send.config();
+            // * it returns the config
+            assert_eq!(config.admin, admin());
+            assert_eq!(config.fee, MOCK_FEE);
+            assert_eq!(
+                config.azero_id_router_address,
+                mock_azero_id_router_address()
+            );
+        }
+
+        #[ink::test]
+        fn test_cheque_status_decoding() {
+            let accounts: DefaultAccounts<DefaultEnvironment> = default_accounts();
+            let statuses = [
+                ChequeStatus::PendingCollection,
+                ChequeStatus::Collected,
+                ChequeStatus::Cancelled,
+            ];
+            for (legacy_status, status) in statuses.into_iter().enumerate() {
+                // * it decodes statuses stored as a u8
+                let decoded: ChequeStatus =
+                    scale::Decode::decode(&mut &[legacy_status as u8][..]).unwrap();
+                assert_eq!(decoded, status);
+                // * it decodes cheques stored with the original layout
+                let legacy_cheque = LegacyCheque {
+                    id: 1,
+                    from: accounts.alice,
+                    to: accounts.bob,
+                    amount: MOCK_AMOUNT,
+                    token_address: Some(token_address()),
+                    status: legacy_status as u8,
+                    fee: MOCK_FEE,
+                    memo: Some("345123".to_string()),
+                };
+                let decoded_legacy_cheque: LegacyCheque =
+                    scale::Decode::decode(&mut &scale::Encode::encode(&legacy_cheque)[..]).unwrap();
+                let cheque: Cheque = decoded_legacy_cheque.into();
+                assert_eq!(cheque.status, status);
+                assert_eq!(cheque.to, Some(legacy_cheque.to));
+                assert_eq!(cheque.memo, legacy_cheque.memo);
+                // * it doesn't decode them with the current layout
+                let result: core::result::Result<Cheque, scale::Error> =
+                    scale::Decode::decode(&mut &scale::Encode::encode(&legacy_cheque)[..]);
+                assert!(result.is_err());
+            }
+        }
+
+        #[ink::test]
+        fn test_resolver_for() {
+            let (accounts, mut az_safe_send) = init();
+            let router: Resolver = AzSafeSend::azero_id_router(mock_azero_id_router_address());
+            // when TLD doesn't have a resolver
+            // * it returns the AZERO.ID router
+            assert_eq!(az_safe_send.resolver_for("bob.tzero"), router);
+            // when TLD has a resolver
+            let resolver: Resolver = Resolver {
+                address: accounts.charlie,
+                selector: AZERO_ID_GET_ADDRESS_SELECTOR,
+                return_type: ResolverReturnType::OptionalAccountId,
+            };
+            az_safe_send
+                .resolvers
+                .insert("tzero".to_string(), &resolver);
+            // * it returns the resolver for domains ending in the TLD
+            assert_eq!(az_safe_send.resolver_for("bob.tzero"), resolver);
+            assert_eq!(az_safe_send.resolver_for("alice.bob.tzero"), resolver);
+            assert_eq!(az_safe_send.resolver_for("tzero"), resolver);
+            // * it ignores the case of the TLD
+            assert_eq!(az_safe_send.resolver_for("bob.TZERO"), resolver);
+            assert_eq!(az_safe_send.resolver_for("bob.tZeRo"), resolver);
+            // * it returns the AZERO.ID router for other domains
+            assert_eq!(az_safe_send.resolver_for("bob.azero"), router);
+            assert_eq!(az_safe_send.resolver_for("tzero.azero"), router);
+        }
+
+        // === TEST HANDLES ===
+        #[ink::test]
+        fn test_add_resolver() {
+            let (accounts, mut az_safe_send) = init();
+            // when called by non-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // * it raises an error
+            let mut result = az_safe_send.add_resolver(
+                "tzero".to_string(),
+                accounts.charlie,
+                AZERO_ID_GET_ADDRESS_SELECTOR,
+                ResolverReturnType::AccountIdResult,
+                "bob.tzero".to_string(),
+            );
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            // = when TLD is empty or has more than one label
+            // = * it raises an error
+            for tld in ["", ".", "bob.tzero", ".bob.tzero", "..tzero"] {
+                result = az_safe_send.add_resolver(
+                    tld.to_string(),
+                    accounts.charlie,
+                    AZERO_ID_GET_ADDRESS_SELECTOR,
+                    ResolverReturnType::AccountIdResult,
+                    "bob.tzero".to_string(),
+                );
+                assert_eq!(
+                    result,
+                    Err(AzSafeSendError::UnprocessableEntity(
+                        "TLD is invalid.".to_string()
+                    ))
+                );
+            }
+        }
+
+        #[ink::test]
+        fn test_approve() {
+            let (accounts, mut az_safe_send) = init();
+            // when cheque doesn't exist
+            // * it raises an error
+            let mut result = az_safe_send.approve(0);
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            let cheque: Cheque = create_approver_gated(&mut az_safe_send, &accounts);
+            // = when called by someone who isn't an approver
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = * it raises an error
+            result = az_safe_send.approve(cheque.id);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when called by an approver
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            result = az_safe_send.approve(cheque.id);
+            // = * it records the approval
+            assert_eq!(result.unwrap().approvals, vec![accounts.charlie]);
+            // == when they have already approved
+            // == * it raises an error
+            result = az_safe_send.approve(cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has already been approved by caller.".to_string()
+                ))
+            );
+            // == when the threshold hasn't been met
+            // == * the recipient can't collect
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            result = az_safe_send.collect(cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has not been approved.".to_string()
+                ))
+            );
+            // == when the threshold has been met
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            az_safe_send.approve(cheque.id).unwrap();
+            // == * the sender can't cancel
+            set_caller::<DefaultEnvironment>(admin());
+            result = az_safe_send.cancel(cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has been approved.".to_string()
+                ))
+            );
+            // == * the recipient can collect
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_balance(accounts.bob, 1_000_000);
+            result = az_safe_send.collect(cheque.id);
+            assert_eq!(result.unwrap().status, ChequeStatus::Collected);
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + MOCK_AMOUNT);
+        }
+
+        #[ink::test]
+        fn test_cancel() {
+            let (accounts, mut az_safe_send) = init();
+            // when cheque doesn't exist
+            let mut result = az_safe_send.cancel(0);
+            // * it raises an error
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            let mut cheque: Cheque = az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = when cheque doesn't belong to caller
+            // = * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            result = az_safe_send.cancel(0);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when cheque belongs to caller
+            set_caller::<DefaultEnvironment>(admin());
+            // == when cheque is finalised
+            cheque.status = ChequeStatus::Collected;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.cancel(0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == when cheque is cancelled
+            cheque.status = ChequeStatus::Cancelled;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.cancel(0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == when cheque is pending
+            cheque.status = ChequeStatus::PendingCollection;
+            // === when cheque has a fee associated with it
+            // ==== when cheque has a token address (TESTED BELOW IN INTEGRATION TEST)
+            // ==== when cheque does not have a token address
+            cheque.recipient_azero_id = Some("receiver.azero".to_string());
+            cheque.sender_azero_id = Some("sender.azero".to_string());
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // ===== * it sends the fee and amount back to the user
+            set_balance(accounts.alice, 1_000_000);
+            az_safe_send.cancel(0).unwrap();
+            assert_eq!(
+                get_balance(accounts.alice),
+                1_000_000 + cheque.fee + cheque.amount
+            );
+            // ===== * it emits an event with the AZERO.IDs
+            if let Event::Cancel(event) = last_event() {
+                assert_eq!(event.id, cheque.id);
+                assert_eq!(event.recipient_azero_id, cheque.recipient_azero_id);
+                assert_eq!(event.sender_azero_id, cheque.sender_azero_id);
+            } else {
+                panic!("expected a Cancel event");
+            }
+            cheque.recipient_azero_id = None;
+            cheque.sender_azero_id = None;
+
+            // === when cheque does not have a fee associated with it
+            cheque.status = ChequeStatus::PendingCollection;
+            cheque.fee = 0;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // ==== when cheque has a token address (TESTED BELOW IN INTEGRATION TEST)
+            // ==== when cheque does not have a token address
+            // ===== * it sends the fee and amount back to the user
+            set_balance(accounts.alice, 1_000_000);
+            az_safe_send.cancel(0).unwrap();
+            assert_eq!(get_balance(accounts.alice), 1_000_000 + cheque.amount);
+            // == * it sets the status to cancelled
+            let mut cheque: Cheque = az_safe_send.cheques.get(cheque.id).unwrap();
+            assert_eq!(cheque.status, ChequeStatus::Cancelled);
+
+            // == when cheque has been partially collected
+            cheque.status = ChequeStatus::PendingCollection;
+            cheque.fee = MOCK_FEE;
+            cheque.collected_amount = 100;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            set_balance(accounts.alice, 1_000_000);
+            let mut result_unwrapped = az_safe_send.cancel(0).unwrap();
+            // == * it sends the uncollected amount back to the user without the fee
+            assert_eq!(get_balance(accounts.alice), 1_000_000 + cheque.amount - 100);
+            // == * it sets the status to cancelled
+            assert_eq!(result_unwrapped.status, ChequeStatus::Cancelled);
+
+            // == when cheque is vesting
+            cheque.collected_amount = 0;
+            cheque.amount = 100;
+            cheque.vest_start = Some(10);
+            cheque.vest_end = Some(20);
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === when nothing has vested
+            set_block_timestamp::<DefaultEnvironment>(10);
+            set_balance(accounts.alice, 1_000_000);
+            result_unwrapped = az_safe_send.cancel(0).unwrap();
+            // === * it sends the fee and amount back to the user
+            assert_eq!(get_balance(accounts.alice), 1_000_000 + MOCK_FEE + 100);
+            // === * it sets the status to cancelled
+            assert_eq!(result_unwrapped.status, ChequeStatus::Cancelled);
+            // === when part has vested
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            set_block_timestamp::<DefaultEnvironment>(15);
+            set_balance(accounts.alice, 1_000_000);
+            result_unwrapped = az_safe_send.cancel(0).unwrap();
+            // === * it sends the unvested amount back to the user
+            assert_eq!(get_balance(accounts.alice), 1_000_000 + 50);
+            // === * it ends vesting with the vested amount
+            assert_eq!(result_unwrapped.amount, 50);
+            assert_eq!(result_unwrapped.vest_end, Some(15));
+            // === * it leaves the vested amount collectable
+            assert_eq!(result_unwrapped.status, ChequeStatus::PendingCollection);
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_balance(accounts.bob, 1_000_000);
+            result_unwrapped = az_safe_send.collect(0).unwrap();
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + 50);
+            assert_eq!(result_unwrapped.status, ChequeStatus::Collected);
+            // === when everything has vested
+            set_caller::<DefaultEnvironment>(admin());
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            set_block_timestamp::<DefaultEnvironment>(20);
+            // === * it raises an error
+            result = az_safe_send.cancel(0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque is fully vested.".to_string()
+                ))
+            );
+
+            // = when cheque is split
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(MOCK_FEE + 300);
+            cheque = az_safe_send
+                .create_split(
+                    vec![(accounts.bob, 100, None), (accounts.charlie, 200, None)],
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_safe_send.collect(cheque.id).unwrap();
+            // == when some of the recipients have collected their shares
+            set_caller::<DefaultEnvironment>(admin());
+            set_balance(accounts.alice, 1_000_000);
+            result_unwrapped = az_safe_send.cancel(cheque.id).unwrap();
+            // == * it sends the uncollected shares back to the user without the fee
+            assert_eq!(get_balance(accounts.alice), 1_000_000 + 200);
+            // == * it sets the status to cancelled
+            assert_eq!(result_unwrapped.status, ChequeStatus::Cancelled);
+
+            // = when cheque is recurring
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                (MOCK_FEE + 100) * 3,
+            );
+            cheque = az_safe_send
+                .create_recurring(accounts.bob, 100, None, None, None, None, 10, 3, None)
+                .unwrap();
+            // == when some instalments have become due
+            set_block_timestamp::<DefaultEnvironment>(20 + 15);
+            set_balance(accounts.alice, 1_000_000);
+            result_unwrapped = az_safe_send.cancel(cheque.id).unwrap();
+            // == * it sends the undue instalments and their fees back to the user
+            assert_eq!(
+                get_balance(accounts.alice),
+                1_000_000 + (MOCK_FEE + 100) * 2
+            );
+            // == * it ends the cheque with the instalments that have become due
+            assert_eq!(result_unwrapped.amount, 100);
+            assert_eq!(result_unwrapped.fee, MOCK_FEE);
+            assert_eq!(result_unwrapped.vest_end, Some(20 + 10));
+            // == * it leaves the due instalments collectable
+            assert_eq!(result_unwrapped.status, ChequeStatus::PendingCollection);
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_balance(accounts.bob, 1_000_000);
+            result_unwrapped = az_safe_send.collect(cheque.id).unwrap();
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + 100);
+            assert_eq!(result_unwrapped.status, ChequeStatus::Collected);
+
+            // = when cheque has a refund address
+            set_caller::<DefaultEnvironment>(admin());
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            cheque = az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    Some(accounts.django),
+                    false,
+                )
+                .unwrap();
+            set_balance(accounts.django, 0);
+            result_unwrapped = az_safe_send.cancel(cheque.id).unwrap();
+            // = * it sends the fee and amount to the refund address
+            assert_eq!(get_balance(accounts.django), MOCK_FEE + MOCK_AMOUNT);
+            assert_eq!(result_unwrapped.refund_to, Some(accounts.django));
+
+            // = when cheque is a claim link with commitments
+            let secret: Vec<u8> = b"secret".to_vec();
+            cheque = create_claim_link(&mut az_safe_send, &secret);
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            az_safe_send
+                .commit_claim(
+                    cheque.id,
+                    az_safe_send.claim_commitment(cheque.id, secret, accounts.charlie),
+                )
+                .unwrap();
+            set_caller::<DefaultEnvironment>(admin());
+            az_safe_send.cancel(cheque.id).unwrap();
+            // = * it removes the commitments
+            assert!(!az_safe_send.claim_commitments.contains(cheque.id));
+        }
+
+        #[ink::test]
+        fn test_cancel_many() {
+            let (accounts, mut az_safe_send) = init();
+            // when batch is empty
+            // * it raises an error
+            let mut result = az_safe_send.cancel_many(vec![], false);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Batch must not be empty.".to_string()
+                ))
+            );
+            // when cheques exist
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                (MOCK_FEE + MOCK_AMOUNT) * 2,
+            );
+            az_safe_send
+                .create_batch(vec![
+                    cheque_params(Some(accounts.charlie), MOCK_AMOUNT),
+                    cheque_params(Some(accounts.charlie), MOCK_AMOUNT),
+                ])
+                .unwrap();
+            // = when called by someone other than the sender
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // == when strict
+            // == * it raises an error
+            result = az_safe_send.cancel_many(vec![0, 1], true);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // == when not strict
+            // == * it returns an error for each cheque
+            result = az_safe_send.cancel_many(vec![0, 1], false);
+            assert_eq!(
+                result,
+                Ok(vec![
+                    Err(AzSafeSendError::Unauthorised),
+                    Err(AzSafeSendError::Unauthorised)
+                ])
+            );
+            // = when called by the sender
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_balance(accounts.bob, 0);
+            let results: Vec<Result<Cheque>> = az_safe_send.cancel_many(vec![0, 2], false).unwrap();
+            // = * it cancels the cheques that can be cancelled
+            assert_eq!(results[0].as_ref().unwrap().status, ChequeStatus::Cancelled);
+            // = * it returns an error for the ones that can't
+            assert_eq!(
+                results[1],
+                Err(AzSafeSendError::NotFound("Cheque".to_string()))
+            );
+            // = * it returns the fee and amount of cancelled cheques to the sender
+            assert_eq!(get_balance(accounts.bob), MOCK_FEE + MOCK_AMOUNT);
+            // = * it leaves the other cheques alone
+            assert_eq!(
+                az_safe_send.show(1).unwrap().status,
+                ChequeStatus::PendingCollection
+            );
+        }
+
+        #[ink::test]
+        fn test_claim() {
+            let (accounts, mut az_safe_send) = init();
+            let secret: Vec<u8> = b"secret".to_vec();
+            // when cheque doesn't exist
+            let mut result = az_safe_send.claim(0, secret.clone(), accounts.charlie);
+            // * it raises an error
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            // = when cheque is not a claim link
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = * it raises an error
+            result = az_safe_send.claim(0, secret.clone(), accounts.charlie);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque is not a claim link.".to_string()
+                ))
+            );
+            // = when cheque is a claim link
+            let mut cheque: Cheque = create_claim_link(&mut az_safe_send, &secret);
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // == when cheque is not pending
+            cheque.status = ChequeStatus::Cancelled;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.claim(1, secret.clone(), accounts.charlie);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == when cheque is pending
+            cheque.status = ChequeStatus::PendingCollection;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === when claim hasn't been committed to
+            // === * it raises an error
+            result = az_safe_send.claim(1, secret.clone(), accounts.charlie);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::NotFound("Commitment".to_string()))
+            );
+            // === when claim has been committed to in the current block
+            let commitment: Hash =
+                az_safe_send.claim_commitment(1, secret.clone(), accounts.charlie);
+            az_safe_send.commit_claim(1, commitment).unwrap();
+            // === * it raises an error
+            result = az_safe_send.claim(1, secret.clone(), accounts.charlie);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Claim must be committed in an earlier block.".to_string()
+                ))
+            );
+            // === when claim has been committed to in an earlier block
+            let wrong_secret: Vec<u8> = b"wrong".to_vec();
+            az_safe_send
+                .commit_claim(
+                    1,
+                    az_safe_send.claim_commitment(1, wrong_secret.clone(), accounts.charlie),
+                )
+                .unwrap();
+            ink::env::test::advance_block::<DefaultEnvironment>();
+            // ==== when secret is incorrect
+            // ==== * it raises an error
+            result = az_safe_send.claim(1, wrong_secret, accounts.charlie);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // ==== when secret is correct
+            set_balance(accounts.charlie, 1_000_000);
+            let result_unwrapped = az_safe_send
+                .claim(1, secret.clone(), accounts.charlie)
+                .unwrap();
+            // ==== * it transfers the cheque amount to the beneficiary
+            assert_eq!(get_balance(accounts.charlie), 1_000_000 + MOCK_AMOUNT);
+            // ==== * it addresses the cheque to the beneficiary
+            assert_eq!(result_unwrapped.to, Some(accounts.charlie));
+            assert_eq!(result_unwrapped.claim_hash, None);
+            // ==== * it removes the commitment
+            assert!(!az_safe_send.claim_commitments.contains(1));
+            // ==== * it sets the status to collected
+            assert_eq!(result_unwrapped.status, ChequeStatus::Collected);
+        }
+
+        // This is for cheques without a token address attached to it
+        #[ink::test]
+        fn test_collect() {
+            let (accounts, mut az_safe_send) = init();
+            // when cheque doesn't exist
+            let mut result = az_safe_send.collect(1);
+            // * it raises an error
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            let mut cheque: Cheque = az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = when cheque's to isn't the caller
+            // = * it raises an error
+            result = az_safe_send.collect(0);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when cheque's to is the caller
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // == when cheque is collected
+            cheque.status = ChequeStatus::Collected;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.collect(0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == when cheque is cancelled
+            cheque.status = ChequeStatus::Cancelled;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.collect(0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == when cheque is pending
+            cheque.status = ChequeStatus::PendingCollection;
+            // === when cheque has expired
+            cheque.expires_at = Some(1);
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            set_block_timestamp::<DefaultEnvironment>(1);
+            // === * it raises an error
+            result = az_safe_send.collect(0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has expired.".to_string()
+                ))
+            );
+            // === when cheque has not expired
+            cheque.expires_at = Some(5);
+            // ==== when cheque is locked
+            cheque.unlock_at = Some(3);
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // ==== * it raises an error
+            result = az_safe_send.collect(0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque is locked.".to_string()
+                ))
+            );
+            // ==== * it raises an error right up until the unlock time
+            set_block_timestamp::<DefaultEnvironment>(2);
+            result = az_safe_send.collect(0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque is locked.".to_string()
+                ))
+            );
+            // ==== when cheque is unlocked
+            set_block_timestamp::<DefaultEnvironment>(3);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.alice, 1_000_000);
+            let mut stored_cheque: Cheque = az_safe_send.cheques.get(0).unwrap();
+            stored_cheque.recipient_azero_id = Some("receiver.azero".to_string());
+            stored_cheque.sender_azero_id = Some("sender.azero".to_string());
+            az_safe_send.cheques.insert(0, &stored_cheque);
+            result = az_safe_send.collect(0);
+            let result_unwrapped = result.unwrap();
+            // ==== * it transfers the cheque amount to the caller
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + cheque.amount);
+            // ==== * it transfers the fee to the admin
+            assert!(get_balance(accounts.alice) > 1_000_000);
+            // ==== * it sets the status to collected
+            assert_eq!(result_unwrapped.status, ChequeStatus::Collected);
+            // ==== * it emits an event with the AZERO.IDs
+            if let Event::Collect(event) = last_event() {
+                assert_eq!(event.id, 0);
+                assert_eq!(event.amount, cheque.amount);
+                assert_eq!(event.beneficiary, accounts.bob);
+                assert_eq!(event.recipient_azero_id, stored_cheque.recipient_azero_id);
+                assert_eq!(event.sender_azero_id, stored_cheque.sender_azero_id);
+            } else {
+                panic!("expected a Collect event");
+            }
+
+            // = when cheque is vesting
+            cheque.expires_at = None;
+            cheque.unlock_at = None;
+            cheque.amount = 100;
+            cheque.vest_start = Some(10);
+            cheque.vest_end = Some(20);
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == when nothing has vested
+            set_block_timestamp::<DefaultEnvironment>(10);
+            // == * it raises an error
+            result = az_safe_send.collect(0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Nothing to collect.".to_string()
+                ))
+            );
+            // == when part has vested
+            set_block_timestamp::<DefaultEnvironment>(13);
+            az_safe_send.admin = accounts.eve;
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.eve, 0);
+            let mut result_unwrapped = az_safe_send.collect(0).unwrap();
+            // == * it transfers the vested amount to the caller
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + 30);
+            assert_eq!(result_unwrapped.collected_amount, 30);
+            // == * it transfers the fee to the admin on the first collection
+            assert_eq!(get_balance(accounts.eve), cheque.fee);
+            // == * it keeps the status as pending collection
+            assert_eq!(result_unwrapped.status, ChequeStatus::PendingCollection);
+            // == when more has vested
+            set_block_timestamp::<DefaultEnvironment>(19);
+            result_unwrapped = az_safe_send.collect(0).unwrap();
+            // == * it transfers the vested but uncollected amount to the caller
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + 90);
+            assert_eq!(result_unwrapped.collected_amount, 90);
+            // == * it doesn't transfer the fee again
+            assert_eq!(get_balance(accounts.eve), cheque.fee);
+            // == when everything has vested
+            set_block_timestamp::<DefaultEnvironment>(25);
+            result_unwrapped = az_safe_send.collect(0).unwrap();
+            // == * it transfers the remainder to the caller
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + 100);
+            // == * it sets the status to collected
+            assert_eq!(result_unwrapped.status, ChequeStatus::Collected);
+
+            // = when cheque is split
+            set_caller::<DefaultEnvironment>(admin());
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(MOCK_FEE + 300);
+            cheque = az_safe_send
+                .create_split(
+                    vec![(accounts.bob, 100, None), (accounts.charlie, 200, None)],
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+            // == when called by someone who isn't a recipient
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // == * it raises an error
+            result = az_safe_send.collect(cheque.id);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // == when called by a recipient
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            set_balance(accounts.charlie, 1_000_000);
+            set_balance(accounts.eve, 0);
+            result_unwrapped = az_safe_send.collect(cheque.id).unwrap();
+            // == * it transfers their share to the caller
+            assert_eq!(get_balance(accounts.charlie), 1_000_000 + 200);
+            // == * it transfers the fee to the admin on the first collection
+            assert_eq!(get_balance(accounts.eve), MOCK_FEE);
+            // == * it keeps the status as pending collection
+            assert_eq!(result_unwrapped.status, ChequeStatus::PendingCollection);
+            // === when they have already collected their share
+            // === * it raises an error
+            result = az_safe_send.collect(cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Share has already been collected.".to_string()
+                ))
+            );
+            // == when the last recipient collects
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            set_balance(accounts.bob, 1_000_000);
+            result_unwrapped = az_safe_send.collect(cheque.id).unwrap();
+            // == * it transfers their share to the caller
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + 100);
+            // == * it doesn't transfer the fee again
+            assert_eq!(get_balance(accounts.eve), MOCK_FEE);
+            // == * it sets the status to collected
+            assert_eq!(result_unwrapped.status, ChequeStatus::Collected);
+
+            // = when cheque is recurring
+            set_caller::<DefaultEnvironment>(admin());
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                (MOCK_FEE + 100) * 3,
+            );
+            cheque = az_safe_send
+                .create_recurring(accounts.bob, 100, None, None, None, None, 10, 3, None)
+                .unwrap();
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // == when no instalments have become due
+            set_block_timestamp::<DefaultEnvironment>(25 + 9);
+            // == * it raises an error
+            result = az_safe_send.collect(cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Nothing to collect.".to_string()
+                ))
+            );
+            // == when some instalments have become due
+            set_block_timestamp::<DefaultEnvironment>(25 + 20);
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.eve, 0);
+            result_unwrapped = az_safe_send.collect(cheque.id).unwrap();
+            // == * it transfers the due instalments to the caller
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + 200);
+            // == * it transfers the fee for each collected instalment to the admin
+            assert_eq!(get_balance(accounts.eve), MOCK_FEE * 2);
+            // == * it keeps the status as pending collection
+            assert_eq!(result_unwrapped.status, ChequeStatus::PendingCollection);
+            // == when the last instalment has become due
+            set_block_timestamp::<DefaultEnvironment>(25 + 30);
+            result_unwrapped = az_safe_send.collect(cheque.id).unwrap();
+            // == * it transfers the last instalment and its fee
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + 300);
+            assert_eq!(get_balance(accounts.eve), MOCK_FEE * 3);
+            // == * it sets the status to collected
+            assert_eq!(result_unwrapped.status, ChequeStatus::Collected);
+        }
+
+        #[ink::test]
+        fn test_collect_many() {
+            let (accounts, mut az_safe_send) = init();
+            az_safe_send.admin = accounts.eve;
+            // when batch is too large
+            // * it raises an error
+            let mut result = az_safe_send.collect_many(vec![0; MAX_BATCH_SIZE + 1], false);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Batch is too large.".to_string()
+                ))
+            );
+            // when cheques exist
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                (MOCK_FEE + MOCK_AMOUNT) * 2,
+            );
+            az_safe_send
+                .create_batch(vec![
+                    cheque_params(Some(accounts.charlie), MOCK_AMOUNT),
+                    cheque_params(Some(accounts.charlie), MOCK_AMOUNT),
+                ])
+                .unwrap();
+            // = when called by the recipient
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // == when strict and one of the cheques can't be collected
+            // == * it raises an error
+            result = az_safe_send.collect_many(vec![2, 0], true);
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // == when not strict
+            set_balance(accounts.charlie, 0);
+            set_balance(accounts.eve, 0);
+            let results: Vec<Result<Cheque>> =
+                az_safe_send.collect_many(vec![0, 1, 0], false).unwrap();
+            // == * it collects the cheques that can be collected
+            assert_eq!(results[0].as_ref().unwrap().status, ChequeStatus::Collected);
+            assert_eq!(results[1].as_ref().unwrap().status, ChequeStatus::Collected);
+            // == * it returns an error for the ones that can't
+            assert_eq!(
+                results[2],
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == * it transfers the total amount to the recipient
+            assert_eq!(get_balance(accounts.charlie), MOCK_AMOUNT * 2);
+            // == * it transfers the total fee to the admin
+            assert_eq!(get_balance(accounts.eve), MOCK_FEE * 2);
+        }
+
+        #[ink::test]
+        fn test_collect_partial() {
+            let (accounts, mut az_safe_send) = init();
+            az_safe_send.admin = accounts.eve;
+            // when cheque doesn't exist
+            let mut result = az_safe_send.collect_partial(0, 1);
+            // * it raises an error
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = when cheque's to isn't the caller
+            // = * it raises an error
+            result = az_safe_send.collect_partial(0, 1);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when cheque's to is the caller
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // == when amount is zero
+            // == * it raises an error
+            result = az_safe_send.collect_partial(0, 0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount must be greater than zero.".to_string()
+                ))
+            );
+            // == when amount is greater than the collectable amount
+            // == * it raises an error
+            result = az_safe_send.collect_partial(0, MOCK_AMOUNT + 1);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount must be less than or equal to collectable amount.".to_string()
+                ))
+            );
+            // == when amount is less than the collectable amount
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.eve, 0);
+            let mut result_unwrapped = az_safe_send.collect_partial(0, 100).unwrap();
+            // == * it transfers the amount to the caller
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + 100);
+            // == * it records the collected amount
+            assert_eq!(result_unwrapped.collected_amount, 100);
+            // == * it transfers the fee to the admin on the first collection
+            assert_eq!(get_balance(accounts.eve), MOCK_FEE);
+            // == * it keeps the status as pending collection
+            assert_eq!(result_unwrapped.status, ChequeStatus::PendingCollection);
+            // == when amount is the remaining collectable amount
+            result_unwrapped = az_safe_send.collect_partial(0, MOCK_AMOUNT - 100).unwrap();
+            // == * it transfers the amount to the caller
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + MOCK_AMOUNT);
+            // == * it doesn't transfer the fee again
+            assert_eq!(get_balance(accounts.eve), MOCK_FEE);
+            // == * it sets the status to collected
+            assert_eq!(result_unwrapped.status, ChequeStatus::Collected);
+        }
+
+        #[ink::test]
+        fn test_collect_to() {
+            let (accounts, mut az_safe_send) = init();
+            // when cheque doesn't exist
+            let mut result = az_safe_send.collect_to(0, accounts.charlie);
+            // * it raises an error
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = when cheque's to isn't the caller
+            // = * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            result = az_safe_send.collect_to(0, accounts.charlie);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when cheque's to is the caller
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // == when beneficiary is the contract
+            // == * it raises an error
+            result = az_safe_send.collect_to(0, ink::env::account_id::<DefaultEnvironment>());
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Beneficiary must not be this contract.".to_string()
+                ))
+            );
+            // == when beneficiary is another account
+            set_balance(accounts.bob, 1_000_000);
+            set_balance(accounts.charlie, 1_000_000);
+            let result_unwrapped = az_safe_send.collect_to(0, accounts.charlie).unwrap();
+            // == * it transfers the cheque amount to the beneficiary
+            assert_eq!(get_balance(accounts.charlie), 1_000_000 + MOCK_AMOUNT);
+            assert_eq!(get_balance(accounts.bob), 1_000_000);
+            // == * it records the beneficiary
+            assert_eq!(result_unwrapped.beneficiary, Some(accounts.charlie));
+            // == * it sets the status to collected
+            assert_eq!(result_unwrapped.status, ChequeStatus::Collected);
+        }
+
+        #[ink::test]
+        fn test_collect_with_signature() {
+            let (accounts, mut az_safe_send) = init();
+            let recipient_secret_key: SecretKey = SecretKey::from_slice(&[1; 32]).unwrap();
+            let other_secret_key: SecretKey = SecretKey::from_slice(&[2; 32]).unwrap();
+            let recipient: AccountId = ecdsa_account_id(&recipient_secret_key);
+            let tip: Balance = 10;
+            // when cheque doesn't exist
+            let mut result = az_safe_send.collect_with_signature(0, accounts.eve, 10, tip, [0; 65]);
+            // * it raises an error
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            az_safe_send
+                .create(
+                    Some(recipient),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = when called by a relayer
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            let message_hash: Hash = az_safe_send
+                .collect_signature_hash(0, accounts.eve, 10, tip)
+                .unwrap();
+            // == when deadline has passed
+            set_block_timestamp::<DefaultEnvironment>(11);
+            // == * it raises an error
+            result = az_safe_send.collect_with_signature(
+                0,
+                accounts.eve,
+                10,
+                tip,
+                ecdsa_sign(&recipient_secret_key, message_hash),
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Signature has expired.".to_string()
+                ))
+            );
+            // == when deadline hasn't passed
+            set_block_timestamp::<DefaultEnvironment>(10);
+            // === when signed by someone other than the recipient
+            // === * it raises an error
+            result = az_safe_send.collect_with_signature(
+                0,
+                accounts.eve,
+                10,
+                tip,
+                ecdsa_sign(&other_secret_key, message_hash),
+            );
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // === when signed by the recipient
+            // ==== when tip isn't less than the collectable amount
+            let message_hash_with_large_tip: Hash = az_safe_send
+                .collect_signature_hash(0, accounts.eve, 10, MOCK_AMOUNT)
+                .unwrap();
+            // ==== * it raises an error
+            result = az_safe_send.collect_with_signature(
+                0,
+                accounts.eve,
+                10,
+                MOCK_AMOUNT,
+                ecdsa_sign(&recipient_secret_key, message_hash_with_large_tip),
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Tip must be less than collectable amount.".to_string()
+                ))
+            );
+            // ==== when tip is less than the collectable amount
+            set_balance(accounts.charlie, 1_000_000);
+            set_balance(accounts.eve, 0);
+            let signature: [u8; 65] = ecdsa_sign(&recipient_secret_key, message_hash);
+            let result_unwrapped = az_safe_send
+                .collect_with_signature(0, accounts.eve, 10, tip, signature)
+                .unwrap();
+            // ==== * it transfers the collectable amount minus the tip to the beneficiary
+            assert_eq!(get_balance(accounts.eve), MOCK_AMOUNT - tip);
+            // ==== * it transfers the tip to the relayer
+            assert_eq!(get_balance(accounts.charlie), 1_000_000 + tip);
+            // ==== * it records the beneficiary
+            assert_eq!(result_unwrapped.beneficiary, Some(accounts.eve));
+            // ==== * it sets the status to collected
+            assert_eq!(result_unwrapped.status, ChequeStatus::Collected);
+            // ==== * it increments the recipient's nonce
+            assert_eq!(az_safe_send.nonce(recipient), 1);
+            // ==== when the signature is replayed
+            // ==== * it raises an error
+            result = az_safe_send.collect_with_signature(0, accounts.eve, 10, tip, signature);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+        }
+
+        #[ink::test]
+        fn test_commit_claim() {
+            let (accounts, mut az_safe_send) = init();
+            let secret: Vec<u8> = b"secret".to_vec();
+            let commitment: Hash =
+                az_safe_send.claim_commitment(0, secret.clone(), accounts.charlie);
+            // when cheque doesn't exist
+            let mut result = az_safe_send.commit_claim(0, commitment);
+            // * it raises an error
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            // = when cheque is not a claim link
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = * it raises an error
+            result = az_safe_send.commit_claim(0, commitment);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque is not a claim link.".to_string()
+                ))
+            );
+            // = when cheque is a claim link
+            let mut cheque: Cheque = create_claim_link(&mut az_safe_send, &secret);
+            set_caller::<DefaultEnvironment>(accounts.django);
+            // == when cheque is not pending
+            cheque.status = ChequeStatus::Cancelled;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.commit_claim(1, commitment);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == when cheque has expired
+            cheque.status = ChequeStatus::PendingCollection;
+            cheque.expires_at = Some(0);
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.commit_claim(1, commitment);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has expired.".to_string()
+                ))
+            );
+            cheque.expires_at = None;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == when commitment is new
+            ink::env::test::advance_block::<DefaultEnvironment>();
+            az_safe_send.commit_claim(1, commitment).unwrap();
+            // == * it stores the block number of the commitment
+            assert_eq!(
+                az_safe_send.claim_commitments.get(1),
+                Some(vec![(commitment, 1)])
+            );
+            // == when commitment already exists
+            // == * it raises an error
+            result = az_safe_send.commit_claim(1, commitment);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Commitment already exists.".to_string()
+                ))
+            );
+            // == when the claim link has the maximum number of commitments
+            for beneficiary in 1..MAX_CLAIM_COMMITMENTS {
+                let commitment: Hash = az_safe_send.claim_commitment(
+                    1,
+                    secret.clone(),
+                    AccountId::from([100 + beneficiary as u8; 32]),
+                );
+                az_safe_send.commit_claim(1, commitment).unwrap();
+            }
+            // == * it raises an error
+            result = az_safe_send
+                .commit_claim(1, az_safe_send.claim_commitment(1, secret, accounts.eve));
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::RecordsLimitReached(
+                    "Commitment".to_string()
+                ))
+            );
+        }
+
+        // Testing here when token address isn't provided
+        // Testing with token address in e2e tests below
+        #[ink::test]
+        fn test_create() {
+            let (accounts, mut az_safe_send) = init();
+            let memo = Some("345123".to_string());
+            // when neither or both of a receiver and a claim hash are provided
+            // * it raises an error
+            for (to, claim_hash) in [
+                (None, None),
+                (Some(accounts.bob), Some(claim_hash(b"secret"))),
+            ] {
+                let result = az_safe_send.create(
+                    to,
+                    1,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    claim_hash,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                );
+                assert_eq!(
+                    result,
+                    Err(AzSafeSendError::UnprocessableEntity(
+                        "Either a receiver or a claim hash must be provided.".to_string()
+                    ))
+                );
+            }
+            // when a receiver AZERO.ID is provided with a claim hash
+            // * it raises an error
+            let mut result = az_safe_send.create(
+                None,
+                1,
+                None,
+                None,
+                Some("receiver.azero".to_string()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(claim_hash(b"secret")),
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Either a receiver or a claim hash must be provided.".to_string()
+                ))
+            );
+            // when a receiver AZERO.ID is provided without a receiver
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(MOCK_FEE + 1);
+            // = when an expiry is not provided
+            // = * it raises an error
+            result = az_safe_send.create(
+                None,
+                1,
+                None,
+                None,
+                Some("receiver.azero".to_string()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Expiry is required when the receiver is an AZERO.ID only.".to_string()
+                ))
+            );
+            // = when an expiry is provided
+            let cheque: Cheque = az_safe_send
+                .create(
+                    None,
+                    1,
+                    None,
+                    None,
+                    Some("receiver.azero".to_string()),
+                    None,
+                    Some(10),
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = * it stores a cheque payable to whoever the AZERO.ID resolves to on collection
+            assert_eq!(cheque.to, None);
+            assert_eq!(
+                cheque.recipient_azero_id,
+                Some("receiver.azero".to_string())
+            );
+            // when sender and receiver are the same
+            // * it raises an error
+            result = az_safe_send.create(
+                Some(admin()),
+                1,
+                Some(token_address()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Sender and receiver must be different.".to_string()
+                ))
+            );
+            // when sender and receiver are different
+            // = when amount is zero
+            // = * it raises an error
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                0,
+                Some(token_address()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount must be greater than zero.".to_string()
+                ))
+            );
+            // = when expiry is not in the future
+            // = * it raises an error
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                1,
+                None,
+                None,
+                None,
+                None,
+                Some(0),
+                None,
+                None,
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Expiry must be in the future.".to_string()
+                ))
+            );
+            // = when unlock is not before expiry
+            // = * it raises an error
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                1,
+                None,
+                None,
+                None,
+                None,
+                Some(1),
+                Some(1),
+                None,
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Unlock must be before expiry.".to_string()
+                ))
+            );
+            // = when only one of vesting start and end is provided
+            // = * it raises an error
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                1,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(1),
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Vesting start and end must be provided together.".to_string()
+                ))
+            );
+            // = when vesting end is not after vesting start
+            // = * it raises an error
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                1,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(1),
+                Some(1),
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Vesting end must be after vesting start.".to_string()
+                ))
+            );
+            // = when expiry is not after vesting end
+            // = * it raises an error
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                1,
+                None,
+                None,
+                None,
+                None,
+                Some(2),
+                None,
+                Some(1),
+                Some(2),
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Expiry must be after vesting end.".to_string()
+                ))
+            );
+            // = when approval threshold is more than the number of approvers
+            // = * it raises an error
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                1,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                vec![accounts.charlie],
+                2,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Approval threshold must be between one and the number of approvers."
+                        .to_string()
+                ))
+            );
+            // = when AZERO.ID revalidation is requested without a receiver AZERO.ID
+            // = * it raises an error
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                1,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                true,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "AZERO.ID revalidation requires a receiver AZERO.ID.".to_string()
+                ))
+            );
+            // = when refund address is the contract
+            // = * it raises an error
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                1,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                Some(ink::env::account_id::<DefaultEnvironment>()),
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Refund address must not be this contract.".to_string()
+                ))
+            );
+            // == when new cheque id will be less than or equal to u32::MAX is within range
+            az_safe_send.cheques_total = u32::MAX - 1;
+            // === when token address is not provided
+            // ==== when fee is incorrect
+            let amount: Balance = 1;
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(az_safe_send.fee);
+            // ==== * it raises an error
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                amount,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(result, Err(AzSafeSendError::IncorrectFee));
+            // ==== when fee is correct
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                az_safe_send.fee + amount,
+            );
+            // ==== * it stores the submitter as the caller
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                amount,
+                None,
+                memo.clone(),
+                None,
+                None,
+                Some(2),
+                Some(1),
+                None,
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            let result_unwrapped = result.unwrap();
+            // ==== * it increases the cheque length by 1
+            assert_eq!(az_safe_send.cheques_total, u32::MAX);
+            // ==== * it stores the id as the current length
+            assert_eq!(result_unwrapped.id, u32::MAX - 1);
+            // ==== * it stores the caller as from
+            assert_eq!(result_unwrapped.from, admin());
+            // ==== * it stores the to
+            assert_eq!(result_unwrapped.to, Some(accounts.bob));
+            // ==== * it stores the amount
+            assert_eq!(result_unwrapped.amount, amount);
+            // ==== * it sets the status to pending collection
+            assert_eq!(result_unwrapped.status, ChequeStatus::PendingCollection);
+            // ==== * it stores the submitted token_address
+            assert_eq!(result_unwrapped.token_address, None);
+            // ==== * it stores the submitted memo
+            assert_eq!(result_unwrapped.memo, memo);
+            // ==== * it stores the submitted expiry
+            assert_eq!(result_unwrapped.expires_at, Some(2));
+            // ==== * it stores the submitted unlock time
+            assert_eq!(result_unwrapped.unlock_at, Some(1));
+            // ==== * it stores the transaction
+            assert_eq!(
+                result_unwrapped,
+                az_safe_send.cheques.get(result_unwrapped.id).unwrap()
+            );
+            // == when new cheque id will be greater than u32::MAX
+            result = az_safe_send.create(
+                Some(accounts.bob),
+                1,
+                Some(token_address()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                vec![],
+                0,
+                None,
+                0,
+                None,
+                false,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::RecordsLimitReached("Cheque".to_string()))
+            );
+        }
+
+        #[ink::test]
+        fn test_create_batch() {
+            let (accounts, mut az_safe_send) = init();
+            // when batch is empty
+            // * it raises an error
+            let mut result = az_safe_send.create_batch(vec![]);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Batch must not be empty.".to_string()
+                ))
+            );
+            // when batch is too large
+            // * it raises an error
+            result = az_safe_send.create_batch(vec![
+                cheque_params(Some(accounts.bob), MOCK_AMOUNT);
+                MAX_BATCH_SIZE + 1
+            ]);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Batch is too large.".to_string()
+                ))
+            );
+            // when any of the cheques are invalid
+            // * it raises an error
+            result = az_safe_send.create_batch(vec![
+                cheque_params(Some(accounts.bob), MOCK_AMOUNT),
+                cheque_params(Some(accounts.alice), MOCK_AMOUNT),
+            ]);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Sender and receiver must be different.".to_string()
+                ))
+            );
+            // when all of the cheques are valid
+            let cheques_params: Vec<ChequeParams> = vec![
+                cheque_params(Some(accounts.bob), MOCK_AMOUNT),
+                cheque_params(Some(accounts.charlie), MOCK_AMOUNT + 1),
+            ];
+            // = when AZERO sent in doesn't equal the total of the fees and amounts
+            // = * it raises an error
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE * 2 + MOCK_AMOUNT * 2,
+            );
+            result = az_safe_send.create_batch(cheques_params.clone());
+            assert_eq!(result, Err(AzSafeSendError::IncorrectFee));
+            // = when AZERO sent in equals the total of the fees and amounts
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE * 2 + MOCK_AMOUNT * 2 + 1,
+            );
+            let cheques: Vec<Cheque> = az_safe_send.create_batch(cheques_params).unwrap();
+            // = * it creates a cheque for each entry
+            assert_eq!(cheques.len(), 2);
+            assert_eq!(az_safe_send.cheques_total, 2);
+            assert_eq!(az_safe_send.show(0).unwrap(), cheques[0]);
+            assert_eq!(cheques[0].to, Some(accounts.bob));
+            assert_eq!(cheques[0].amount, MOCK_AMOUNT);
+            assert_eq!(cheques[0].fee, MOCK_FEE);
+            assert_eq!(az_safe_send.show(1).unwrap(), cheques[1]);
+            assert_eq!(cheques[1].to, Some(accounts.charlie));
+            assert_eq!(cheques[1].amount, MOCK_AMOUNT + 1);
+            // = * it emits a create event for each cheque
+            assert_eq!(ink::env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn test_create_recurring() {
+            let (accounts, mut az_safe_send) = init();
+            // when instalments is zero
+            // * it raises an error
+            let mut result = az_safe_send.create_recurring(
+                accounts.bob,
+                MOCK_AMOUNT,
+                None,
+                None,
+                None,
+                None,
+                10,
+                0,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Instalments must be greater than zero.".to_string()
+                ))
+            );
+            // when period is zero
+            // * it raises an error
+            result = az_safe_send.create_recurring(
+                accounts.bob,
+                MOCK_AMOUNT,
+                None,
+                None,
+                None,
+                None,
+                0,
+                3,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Period must be greater than zero.".to_string()
+                ))
+            );
+            // when period and instalments are valid
+            set_block_timestamp::<DefaultEnvironment>(5);
+            // = when AZERO sent in doesn't equal the fee and amount for every instalment
+            // = * it raises an error
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT * 3,
+            );
+            result = az_safe_send.create_recurring(
+                accounts.bob,
+                MOCK_AMOUNT,
+                None,
+                None,
+                None,
+                None,
+                10,
+                3,
+                None,
+            );
+            assert_eq!(result, Err(AzSafeSendError::IncorrectFee));
+            // = when AZERO sent in equals the fee and amount for every instalment
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                (MOCK_FEE + MOCK_AMOUNT) * 3,
+            );
+            let cheque: Cheque = az_safe_send
+                .create_recurring(
+                    accounts.bob,
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    10,
+                    3,
+                    Some(accounts.django),
+                )
+                .unwrap();
+            // = * it stores the cheque with the total of the instalments and their fees
+            assert_eq!(az_safe_send.show(0).unwrap(), cheque);
+            assert_eq!(cheque.amount, MOCK_AMOUNT * 3);
+            assert_eq!(cheque.fee, MOCK_FEE * 3);
+            // = * it schedules an instalment at the end of each period from now
+            assert_eq!(cheque.vest_start, Some(5));
+            assert_eq!(cheque.vest_end, Some(35));
+            assert_eq!(cheque.period, Some(10));
+            // = * it stores the refund address
+            assert_eq!(cheque.refund_to, Some(accounts.django));
+        }
+
+        #[ink::test]
+        fn test_create_split() {
+            let (accounts, mut az_safe_send) = init();
+            // when recipients aren't provided
+            // * it raises an error
+            let mut result = az_safe_send.create_split(vec![], None, None, None, None, None, None);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Recipients must be provided.".to_string()
+                ))
+            );
+            // when there are too many recipients
+            // * it raises an error
+            result = az_safe_send.create_split(
+                vec![(accounts.bob, MOCK_AMOUNT, None); MAX_RECIPIENTS + 1],
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::RecordsLimitReached(
+                    "Recipient".to_string()
+                ))
+            );
+            // when the sender is a recipient
+            // * it raises an error
+            result = az_safe_send.create_split(
+                vec![
+                    (accounts.bob, MOCK_AMOUNT, None),
+                    (accounts.alice, MOCK_AMOUNT, None),
+                ],
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Sender and receiver must be different.".to_string()
+                ))
+            );
+            // when a recipient is repeated
+            // * it raises an error
+            result = az_safe_send.create_split(
+                vec![
+                    (accounts.bob, MOCK_AMOUNT, None),
+                    (accounts.bob, MOCK_AMOUNT, None),
+                ],
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Recipients must be unique.".to_string()
+                ))
+            );
+            // when a share is zero
+            // * it raises an error
+            result = az_safe_send.create_split(
+                vec![
+                    (accounts.bob, MOCK_AMOUNT, None),
+                    (accounts.charlie, 0, None),
+                ],
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount must be greater than zero.".to_string()
+                ))
+            );
+            // when recipients are valid
+            let recipients: Vec<(AccountId, Balance, Option<String>)> = vec![
+                (accounts.bob, MOCK_AMOUNT, None),
+                (accounts.charlie, MOCK_AMOUNT + 1, None),
+            ];
+            // = when refund address is the contract
+            // = * it raises an error
+            result = az_safe_send.create_split(
+                recipients.clone(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(ink::env::account_id::<DefaultEnvironment>()),
+            );
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Refund address must not be this contract.".to_string()
+                ))
+            );
+            // = when AZERO sent in doesn't equal the fee plus the total of the shares
+            // = * it raises an error
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            result =
+                az_safe_send.create_split(recipients.clone(), None, None, None, None, None, None);
+            assert_eq!(result, Err(AzSafeSendError::IncorrectFee));
+            // = when AZERO sent in equals the fee plus the total of the shares
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT * 2 + 1,
+            );
+            let cheque: Cheque = az_safe_send
+                .create_split(
+                    recipients,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(accounts.django),
+                )
+                .unwrap();
+            // = * it stores the cheque with each recipient's share
+            assert_eq!(az_safe_send.show(0).unwrap(), cheque);
+            assert_eq!(cheque.to, None);
+            assert_eq!(cheque.amount, MOCK_AMOUNT * 2 + 1);
+            assert_eq!(
+                cheque.recipients,
+                vec![
+                    (accounts.bob, MOCK_AMOUNT),
+                    (accounts.charlie, MOCK_AMOUNT + 1)
+                ]
+            );
+            assert_eq!(cheque.status, ChequeStatus::PendingCollection);
+            // = * it stores the refund address
+            assert_eq!(cheque.refund_to, Some(accounts.django));
+        }
+
+        #[ink::test]
+        fn test_endorse() {
+            let (accounts, mut az_safe_send) = init();
+            // when cheque doesn't exist
+            let mut result = az_safe_send.endorse(0, accounts.charlie, None);
+            // * it raises an error
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            let mut cheque: Cheque = az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = when cheque's to isn't the caller
+            // = * it raises an error
+            result = az_safe_send.endorse(0, accounts.charlie, None);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when cheque's to is the caller
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // == when cheque is not pending
+            cheque.status = ChequeStatus::Collected;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.endorse(0, accounts.charlie, None);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == when cheque is pending
+            cheque.status = ChequeStatus::PendingCollection;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === when new recipient is the sender or the caller
+            // === * it raises an error
+            for new_to in [accounts.alice, accounts.bob] {
+                result = az_safe_send.endorse(0, new_to, None);
+                assert_eq!(
+                    result,
+                    Err(AzSafeSendError::UnprocessableEntity(
+                        "New recipient must be different from sender and recipient.".to_string()
+                    ))
+                );
+            }
+            // === when new recipient is the arbiter
+            cheque.arbiter = Some(accounts.django);
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === * it raises an error
+            result = az_safe_send.endorse(0, accounts.django, None);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "New recipient must be different from arbiter.".to_string()
+                ))
+            );
+            // === when new recipient is valid
+            let result_unwrapped = az_safe_send.endorse(0, accounts.charlie, None).unwrap();
+            // === * it sets the new recipient
+            assert_eq!(result_unwrapped.to, Some(accounts.charlie));
+            // === * it records the previous recipient
+            assert_eq!(result_unwrapped.endorsements, vec![accounts.bob]);
+            // === * the previous recipient can no longer collect
+            result = az_safe_send.collect(0);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // === when the endorsement limit has been reached
+            cheque.endorsements = vec![accounts.bob; MAX_ENDORSEMENTS];
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === * it raises an error
+            result = az_safe_send.endorse(0, accounts.charlie, None);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::RecordsLimitReached(
+                    "Endorsement".to_string()
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn test_migrate() {
+            let (accounts, mut az_safe_send) = init();
+            let legacy_cheque: LegacyCheque = LegacyCheque {
+                id: 0,
+                from: accounts.django,
+                to: accounts.bob,
+                amount: MOCK_AMOUNT,
+                token_address: None,
+                status: 0,
+                fee: MOCK_FEE,
+                memo: Some("legacy".to_string()),
+            };
+            // Store cheques where the original release's cheques mapping kept them
+            let original_cheques_key: u32 =
+                KeyComposer::compute_key("AzSafeSend", "", "cheques").unwrap();
+            for id in [0_u32, 1] {
+                ink::env::set_contract_storage(
+                    &(original_cheques_key, id),
+                    &LegacyCheque {
+                        id,
+                        ..legacy_cheque.clone()
+                    },
+                );
+            }
+            // when cheque hasn't been migrated
+            // * it can still be read
+            let cheque: Cheque = az_safe_send.show(0).unwrap();
+            assert_eq!(cheque, Cheque::from(legacy_cheque));
+            assert_eq!(cheque.to, Some(accounts.bob));
+            assert_eq!(cheque.status, ChequeStatus::PendingCollection);
+            // when called by non-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // * it raises an error
+            let mut result = az_safe_send.migrate(vec![0]);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // when called by admin
+            set_caller::<DefaultEnvironment>(admin());
+            // = when batch is too large
+            // = * it raises an error
+            result = az_safe_send.migrate(vec![0; MAX_BATCH_SIZE + 1]);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Batch is too large.".to_string()
+                ))
+            );
+            // = when batch size is valid
+            // == when cheque has been updated since the upgrade
+            let mut updated_cheque: Cheque = az_safe_send.show(1).unwrap();
+            updated_cheque.status = ChequeStatus::Collected;
+            az_safe_send.cheques.insert(1, &updated_cheque);
+            az_safe_send.migrate(vec![0, 1, 2]).unwrap();
+            // == * it keeps the updated cheque
+            assert_eq!(az_safe_send.cheques.get(1), Some(updated_cheque));
+            // == * it removes the legacy cheque
+            assert!(!az_safe_send.legacy_cheques.contains(1));
+            // == when cheque hasn't been updated since the upgrade
+            // == * it stores the cheque with the current layout
+            assert_eq!(az_safe_send.cheques.get(0), Some(cheque));
+            // == * it removes the legacy cheque
+            assert!(!az_safe_send.legacy_cheques.contains(0));
+        }
+
+        #[ink::test]
+        fn test_open_dispute() {
+            let (accounts, mut az_safe_send) = init();
+            // when cheque doesn't exist
+            // * it raises an error
+            let mut result = az_safe_send.open_dispute(0);
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            // = when cheque has no arbiter
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = * it raises an error
+            result = az_safe_send.open_dispute(0);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has no arbiter.".to_string()
+                ))
+            );
+            // = when cheque has an arbiter
+            let cheque: Cheque = create_arbitrated(&mut az_safe_send, &accounts);
+            // == when called by someone other than the sender or recipient
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // == * it raises an error
+            result = az_safe_send.open_dispute(cheque.id);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // == when called by the recipient
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            result = az_safe_send.open_dispute(cheque.id);
+            // == * it sets the status to disputed
+            let result_unwrapped: Cheque = result.unwrap();
+            assert_eq!(result_unwrapped.status, ChequeStatus::Disputed);
+            // == * it records when the dispute was opened
+            assert_eq!(result_unwrapped.disputed_at, Some(0));
+            // == * it freezes collection
+            result = az_safe_send.collect(cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == * it freezes cancellation
+            set_caller::<DefaultEnvironment>(accounts.django);
+            result = az_safe_send.cancel(cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+        }
+
+        #[ink::test]
+        fn test_redirect() {
+            let (accounts, mut az_safe_send) = init();
+            // when cheque doesn't exist
+            let mut result = az_safe_send.redirect(0, accounts.charlie, None);
+            // * it raises an error
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            let mut cheque: Cheque = az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = when cheque doesn't belong to caller
+            // = * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            result = az_safe_send.redirect(0, accounts.charlie, None);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when cheque belongs to caller
+            set_caller::<DefaultEnvironment>(admin());
+            // == when cheque is not pending
+            cheque.status = ChequeStatus::Cancelled;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.redirect(0, accounts.charlie, None);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == when cheque is pending
+            cheque.status = ChequeStatus::PendingCollection;
+            // === when cheque has been collected from
+            cheque.collected_amount = 1;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === * it raises an error
+            result = az_safe_send.redirect(0, accounts.charlie, None);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has already been collected from or endorsed.".to_string()
+                ))
+            );
+            // === when cheque has been endorsed
+            cheque.collected_amount = 0;
+            cheque.endorsements = vec![accounts.eve];
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === * it raises an error
+            result = az_safe_send.redirect(0, accounts.charlie, None);
             assert_eq!(
-                config.azero_id_router_address,
-                mock_azero_id_router_address()
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has already been collected from or endorsed.".to_string()
+                ))
+            );
+            // === when cheque hasn't been collected from or endorsed
+            cheque.endorsements = vec![];
+            // ==== when approvals exist
+            cheque.approvers = vec![accounts.django, accounts.eve];
+            cheque.approval_threshold = 2;
+            cheque.approvals = vec![accounts.django];
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // ==== * it raises an error
+            result = az_safe_send.redirect(0, accounts.django, None);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has already been approved.".to_string()
+                ))
+            );
+            // ==== when no approvals exist
+            cheque.approvals = vec![];
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // ===== when new recipient is the sender or the current recipient
+            // ===== * it raises an error
+            for new_to in [accounts.alice, accounts.bob] {
+                result = az_safe_send.redirect(0, new_to, None);
+                assert_eq!(
+                    result,
+                    Err(AzSafeSendError::UnprocessableEntity(
+                        "New recipient must be different from sender and recipient.".to_string()
+                    ))
+                );
+            }
+            // ===== when new recipient is the arbiter
+            cheque.arbiter = Some(accounts.eve);
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // ===== * it raises an error
+            result = az_safe_send.redirect(0, accounts.eve, None);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "New recipient must be different from arbiter.".to_string()
+                ))
             );
+            // ===== when new recipient is valid
+            let result_unwrapped = az_safe_send.redirect(0, accounts.charlie, None).unwrap();
+            // ===== * it sets the new recipient
+            assert_eq!(result_unwrapped.to, Some(accounts.charlie));
+            // ===== * it stores the cheque
+            assert_eq!(result_unwrapped, az_safe_send.cheques.get(0).unwrap());
         }
 
-        // === TEST HANDLES ===
         #[ink::test]
-        fn test_cancel() {
+        fn test_refund_expired() {
             let (accounts, mut az_safe_send) = init();
             // when cheque doesn't exist
-            let mut result = az_safe_send.cancel(0);
+            let mut result = az_safe_send.refund_expired(0);
             // * it raises an error
             assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
             // when cheque exists
@@ -428,72 +5239,158 @@ mod az_safe_send {
                 MOCK_FEE + MOCK_AMOUNT,
             );
             let mut cheque: Cheque = az_safe_send
-                .create(accounts.bob, MOCK_AMOUNT, None, None, None, None)
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(10),
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
                 .unwrap();
-            // = when cheque doesn't belong to caller
-            // = * it raises an error
-            set_caller::<DefaultEnvironment>(accounts.bob);
-            result = az_safe_send.cancel(0);
-            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
-            // = when cheque belongs to caller
-            set_caller::<DefaultEnvironment>(admin());
-            // == when cheque is finalised
-            cheque.status = 1;
-            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // = when called by anyone
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // == when cheque has not expired
+            set_block_timestamp::<DefaultEnvironment>(9);
             // == * it raises an error
-            result = az_safe_send.cancel(0);
+            result = az_safe_send.refund_expired(0);
             assert_eq!(
                 result,
                 Err(AzSafeSendError::UnprocessableEntity(
-                    "Status must be pending collection.".to_string()
+                    "Cheque has not expired.".to_string()
                 ))
             );
-            // == when cheque is cancelled
-            cheque.status = 2;
+            // == when cheque has expired
+            set_block_timestamp::<DefaultEnvironment>(10);
+            // === when cheque is not pending
+            cheque.status = ChequeStatus::Collected;
             az_safe_send.cheques.insert(cheque.id, &cheque);
-            // == * it raises an error
-            result = az_safe_send.cancel(0);
+            // === * it raises an error
+            result = az_safe_send.refund_expired(0);
             assert_eq!(
                 result,
                 Err(AzSafeSendError::UnprocessableEntity(
                     "Status must be pending collection.".to_string()
                 ))
             );
-            // == when cheque is pending
-            cheque.status = 0;
-            // === when cheque has a fee associated with it
-            // ==== when cheque has a token address (TESTED BELOW IN INTEGRATION TEST)
-            // ==== when cheque does not have a token address
+            // === when cheque is pending
+            cheque.status = ChequeStatus::PendingCollection;
             az_safe_send.cheques.insert(cheque.id, &cheque);
-            // ===== * it sends the fee and amount back to the user
             set_balance(accounts.alice, 1_000_000);
-            az_safe_send.cancel(0).unwrap();
+            set_balance(accounts.charlie, 1_000_000);
+            result = az_safe_send.refund_expired(0);
+            // === * it sends the fee and amount back to the sender
             assert_eq!(
                 get_balance(accounts.alice),
                 1_000_000 + cheque.fee + cheque.amount
             );
-
-            // === when cheque does not have a fee associated with it
-            cheque.status = 0;
-            cheque.fee = 0;
-            az_safe_send.cheques.insert(cheque.id, &cheque);
-            // ==== when cheque has a token address (TESTED BELOW IN INTEGRATION TEST)
-            // ==== when cheque does not have a token address
-            // ===== * it sends the fee and amount back to the user
-            set_balance(accounts.alice, 1_000_000);
-            az_safe_send.cancel(0).unwrap();
-            assert_eq!(get_balance(accounts.alice), 1_000_000 + cheque.amount);
-            // == * it sets the status to 2;
-            let cheque: Cheque = az_safe_send.cheques.get(cheque.id).unwrap();
-            assert_eq!(cheque.status, 2);
+            assert_eq!(get_balance(accounts.charlie), 1_000_000);
+            // === * it sets the status to expired
+            assert_eq!(result.unwrap().status, ChequeStatus::Expired);
+            // == when cheque is disputed
+            let disputed_cheque: Cheque = create_arbitrated(&mut az_safe_send, &accounts);
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            az_safe_send.open_dispute(disputed_cheque.id).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            // === when dispute has not timed out
+            set_block_timestamp::<DefaultEnvironment>(10 + DISPUTE_TIMEOUT - 1);
+            // === * it raises an error
+            result = az_safe_send.refund_expired(disputed_cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Dispute has not timed out.".to_string()
+                ))
+            );
+            // === when dispute has timed out
+            set_block_timestamp::<DefaultEnvironment>(10 + DISPUTE_TIMEOUT);
+            set_balance(accounts.charlie, 1_000_000);
+            set_balance(accounts.django, 1_000_000);
+            result = az_safe_send.refund_expired(disputed_cheque.id);
+            // === * it sends the fee and amount back to the sender
+            assert_eq!(
+                get_balance(accounts.django),
+                1_000_000 + disputed_cheque.fee + disputed_cheque.amount
+            );
+            // === * it doesn't pay the arbiter
+            assert_eq!(get_balance(accounts.charlie), 1_000_000);
+            // === * it sets the status to expired
+            assert_eq!(result.unwrap().status, ChequeStatus::Expired);
+            // == when cheque is to an AZERO.ID that hasn't been resolved
+            set_caller::<DefaultEnvironment>(accounts.django);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            let domain_cheque: Cheque = az_safe_send
+                .create(
+                    None,
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    Some("receiver.azero".to_string()),
+                    None,
+                    Some(20 + DISPUTE_TIMEOUT),
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            set_block_timestamp::<DefaultEnvironment>(20 + DISPUTE_TIMEOUT);
+            set_balance(accounts.django, 1_000_000);
+            result = az_safe_send.refund_expired(domain_cheque.id);
+            // == * it sends the fee and amount back to the sender
+            assert_eq!(
+                get_balance(accounts.django),
+                1_000_000 + domain_cheque.fee + domain_cheque.amount
+            );
+            // == * it sets the status to expired
+            assert_eq!(result.unwrap().status, ChequeStatus::Expired);
+            // == when cheque is a claim link with commitments
+            set_caller::<DefaultEnvironment>(accounts.django);
+            let secret: Vec<u8> = b"secret".to_vec();
+            let mut claim_link: Cheque = create_claim_link(&mut az_safe_send, &secret);
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            az_safe_send
+                .commit_claim(
+                    claim_link.id,
+                    az_safe_send.claim_commitment(claim_link.id, secret, accounts.charlie),
+                )
+                .unwrap();
+            claim_link.expires_at = Some(20 + DISPUTE_TIMEOUT);
+            az_safe_send.cheques.insert(claim_link.id, &claim_link);
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            result = az_safe_send.refund_expired(claim_link.id);
+            // == * it sets the status to expired
+            assert_eq!(result.unwrap().status, ChequeStatus::Expired);
+            // == * it removes the commitments
+            assert!(!az_safe_send.claim_commitments.contains(claim_link.id));
         }
 
-        // This is for cheques without a token address attached to it
         #[ink::test]
-        fn test_collect() {
+        fn test_reject() {
             let (accounts, mut az_safe_send) = init();
+            let reason = Some("Wrong token".to_string());
             // when cheque doesn't exist
-            let mut result = az_safe_send.collect(1);
+            let mut result = az_safe_send.reject(0, reason.clone());
             // * it raises an error
             assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
             // when cheque exists
@@ -501,120 +5398,400 @@ mod az_safe_send {
                 MOCK_FEE + MOCK_AMOUNT,
             );
             let mut cheque: Cheque = az_safe_send
-                .create(accounts.bob, MOCK_AMOUNT, None, None, None, None)
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
                 .unwrap();
             // = when cheque's to isn't the caller
             // = * it raises an error
-            result = az_safe_send.collect(0);
+            result = az_safe_send.reject(0, reason.clone());
             assert_eq!(result, Err(AzSafeSendError::Unauthorised));
             // = when cheque's to is the caller
             set_caller::<DefaultEnvironment>(accounts.bob);
-            // == when cheque is collected
-            cheque.status = 1;
+            // == when cheque is not pending
+            cheque.status = ChequeStatus::Cancelled;
             az_safe_send.cheques.insert(cheque.id, &cheque);
             // == * it raises an error
-            result = az_safe_send.collect(0);
+            result = az_safe_send.reject(0, reason.clone());
             assert_eq!(
                 result,
                 Err(AzSafeSendError::UnprocessableEntity(
                     "Status must be pending collection.".to_string()
                 ))
             );
-            // == when cheque is cancelled
-            cheque.status = 2;
+            // == when cheque is pending
+            cheque.status = ChequeStatus::PendingCollection;
             az_safe_send.cheques.insert(cheque.id, &cheque);
+            set_balance(accounts.alice, 1_000_000);
+            set_balance(accounts.bob, 1_000_000);
+            result = az_safe_send.reject(0, reason);
+            // == * it sends the fee and amount back to the sender
+            assert_eq!(
+                get_balance(accounts.alice),
+                1_000_000 + cheque.fee + cheque.amount
+            );
+            assert_eq!(get_balance(accounts.bob), 1_000_000);
+            // == * it sets the status to rejected
+            assert_eq!(result.unwrap().status, ChequeStatus::Rejected);
+        }
+
+        #[ink::test]
+        fn test_remove_resolver() {
+            let (accounts, mut az_safe_send) = init();
+            // when called by non-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // * it raises an error
+            let mut result = az_safe_send.remove_resolver("tzero".to_string());
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // when called by admin
+            set_caller::<DefaultEnvironment>(accounts.alice);
+            // = when resolver doesn't exist
+            // = * it raises an error
+            result = az_safe_send.remove_resolver("tzero".to_string());
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::NotFound("Resolver".to_string()))
+            );
+            // = when TLD is invalid
+            // = * it raises an error
+            result = az_safe_send.remove_resolver("bob.tzero".to_string());
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "TLD is invalid.".to_string()
+                ))
+            );
+            // = when resolver exists
+            let resolver: Resolver = Resolver {
+                address: accounts.charlie,
+                selector: AZERO_ID_GET_ADDRESS_SELECTOR,
+                return_type: ResolverReturnType::OptionalAccountId,
+            };
+            for tld in ["tzero", ".tzero", "TZERO", ".Tzero"] {
+                az_safe_send
+                    .resolvers
+                    .insert("tzero".to_string(), &resolver);
+                // = * it can be looked up with or without a leading dot, in any case
+                assert_eq!(az_safe_send.resolver(tld.to_string()), Ok(resolver.clone()));
+                result = az_safe_send.remove_resolver(tld.to_string());
+                assert!(result.is_ok());
+                // = * it removes the resolver, with or without a leading dot, in any case
+                assert_eq!(
+                    az_safe_send.resolver("tzero".to_string()),
+                    Err(AzSafeSendError::NotFound("Resolver".to_string()))
+                );
+            }
+        }
+
+        #[ink::test]
+        fn test_resolve() {
+            let (accounts, mut az_safe_send) = init();
+            az_safe_send.admin = accounts.eve;
+            // when cheque doesn't exist
+            // * it raises an error
+            let mut result = az_safe_send.resolve(0, 0, 0);
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            let cheque: Cheque = create_arbitrated(&mut az_safe_send, &accounts);
+            // = when called by someone other than the arbiter
+            // = * it raises an error
+            result = az_safe_send.resolve(cheque.id, 0, 200);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when called by the arbiter
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // == when cheque isn't disputed
             // == * it raises an error
-            result = az_safe_send.collect(0);
+            result = az_safe_send.resolve(cheque.id, 0, 200);
             assert_eq!(
                 result,
                 Err(AzSafeSendError::UnprocessableEntity(
-                    "Status must be pending collection.".to_string()
+                    "Status must be disputed.".to_string()
                 ))
             );
-            // == when cheque is pending
-            cheque.status = 0;
-            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == when cheque is disputed
+            set_caller::<DefaultEnvironment>(accounts.django);
+            az_safe_send.open_dispute(cheque.id).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // === when dispute has timed out
+            set_block_timestamp::<DefaultEnvironment>(DISPUTE_TIMEOUT);
+            // === * it raises an error
+            result = az_safe_send.resolve(cheque.id, 120, 80);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Dispute has timed out.".to_string()
+                ))
+            );
+            // === when dispute has not timed out
+            set_block_timestamp::<DefaultEnvironment>(DISPUTE_TIMEOUT - 1);
+            // === when ruling doesn't split the amount less the arbiter fee
+            // === * it raises an error
+            result = az_safe_send.resolve(cheque.id, 100, 150);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Ruling must split the uncollected amount less the arbiter fee.".to_string()
+                ))
+            );
+            // === when ruling splits the amount less the arbiter fee
+            set_balance(accounts.django, 1_000_000);
             set_balance(accounts.bob, 1_000_000);
-            set_balance(accounts.alice, 1_000_000);
-            result = az_safe_send.collect(0);
-            let result_unwrapped = result.unwrap();
-            // == * it transfers the cheque amount to the caller
-            assert_eq!(get_balance(accounts.bob), 1_000_000 + cheque.amount);
-            // == * it transfers the fee to the admin
-            assert!(get_balance(accounts.alice) > 1_000_000);
-            // == * it sets the status to 1;
-            assert_eq!(result_unwrapped.status, 1);
+            set_balance(accounts.charlie, 1_000_000);
+            set_balance(accounts.eve, 0);
+            result = az_safe_send.resolve(cheque.id, 120, 80);
+            // === * it transfers the recipient's portion to the recipient
+            assert_eq!(get_balance(accounts.bob), 1_000_000 + 120);
+            // === * it transfers the sender's portion to the sender
+            assert_eq!(get_balance(accounts.django), 1_000_000 + 80);
+            // === * it transfers the arbiter fee to the arbiter
+            assert_eq!(get_balance(accounts.charlie), 1_000_000 + 50);
+            // === * it transfers the fee to the admin
+            assert_eq!(get_balance(accounts.eve), MOCK_FEE);
+            // === * it sets the status to resolved
+            assert_eq!(result.unwrap().status, ChequeStatus::Resolved);
+            // === when ruling awards the recipient nothing
+            let cheque: Cheque = create_arbitrated(&mut az_safe_send, &accounts);
+            az_safe_send.open_dispute(cheque.id).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            set_balance(accounts.django, 1_000_000);
+            az_safe_send.resolve(cheque.id, 0, 200).unwrap();
+            // === * it returns the fee to the sender
+            assert_eq!(get_balance(accounts.django), 1_000_000 + 200 + MOCK_FEE);
+            assert_eq!(get_balance(accounts.eve), MOCK_FEE);
         }
 
-        // Testing here when token address isn't provided
-        // Testing with token address in e2e tests below
         #[ink::test]
-        fn test_create() {
+        fn test_revoke_approval() {
             let (accounts, mut az_safe_send) = init();
-            let memo = Some("345123".to_string());
-            // when sender and receiver are the same
+            // when cheque doesn't exist
+            // * it raises an error
+            let mut result = az_safe_send.revoke_approval(0);
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            let cheque: Cheque = create_approver_gated(&mut az_safe_send, &accounts);
+            // = when called by someone who isn't an approver
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // = * it raises an error
+            result = az_safe_send.revoke_approval(cheque.id);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when called by an approver
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // == when they haven't approved
+            // == * it raises an error
+            result = az_safe_send.revoke_approval(cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::NotFound("Approval".to_string()))
+            );
+            // == when they have approved
+            az_safe_send.approve(cheque.id).unwrap();
+            // === when the approval threshold has been met
+            set_caller::<DefaultEnvironment>(accounts.eve);
+            az_safe_send.approve(cheque.id).unwrap();
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            // === * it raises an error
+            result = az_safe_send.revoke_approval(cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has been approved.".to_string()
+                ))
+            );
+            // === * the sender still can't cancel
+            set_caller::<DefaultEnvironment>(admin());
+            result = az_safe_send.cancel(cheque.id);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has been approved.".to_string()
+                ))
+            );
+            // === when the approval threshold hasn't been met
+            let cheque: Cheque = create_approver_gated(&mut az_safe_send, &accounts);
+            set_caller::<DefaultEnvironment>(accounts.charlie);
+            az_safe_send.approve(cheque.id).unwrap();
+            result = az_safe_send.revoke_approval(cheque.id);
+            // === * it removes the approval
+            assert_eq!(result.unwrap().approvals, vec![]);
+            // === * the sender can still cancel
+            set_caller::<DefaultEnvironment>(admin());
+            result = az_safe_send.cancel(cheque.id);
+            assert_eq!(result.unwrap().status, ChequeStatus::Cancelled);
+        }
+
+        // Setting the code hash isn't supported off-chain
+        #[ink::test]
+        fn test_set_code() {
+            let (accounts, mut az_safe_send) = init();
+            // when called by non-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // * it raises an error
+            let result = az_safe_send.set_code(Hash::from([1; 32]));
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+        }
+
+        #[ink::test]
+        fn test_top_up() {
+            let (accounts, mut az_safe_send) = init();
+            // when cheque doesn't exist
+            let mut result = az_safe_send.top_up(0, 1);
             // * it raises an error
-            let mut result =
-                az_safe_send.create(admin(), 1, Some(token_address()), None, None, None);
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            let mut cheque: Cheque = az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = when cheque doesn't belong to caller
+            // = * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            result = az_safe_send.top_up(0, 1);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when cheque belongs to caller
+            set_caller::<DefaultEnvironment>(admin());
+            // == when cheque is not pending
+            cheque.status = ChequeStatus::Collected;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.top_up(0, 1);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == when cheque is pending
+            cheque.status = ChequeStatus::PendingCollection;
+            // === when cheque has expired
+            cheque.expires_at = Some(0);
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === * it raises an error
+            result = az_safe_send.top_up(0, 1);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Cheque has expired.".to_string()
+                ))
+            );
+            cheque.expires_at = None;
+            // === when cheque is vesting
+            cheque.vest_start = Some(0);
+            cheque.vest_end = Some(10);
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === * it raises an error
+            result = az_safe_send.top_up(0, 1);
             assert_eq!(
                 result,
                 Err(AzSafeSendError::UnprocessableEntity(
-                    "Sender and receiver must be different.".to_string()
+                    "Vesting cheques can't be topped up.".to_string()
                 ))
             );
-            // when sender and receiver are different
-            // = when amount is zero
-            // = * it raises an error
-            result = az_safe_send.create(accounts.bob, 0, Some(token_address()), None, None, None);
+            cheque.vest_start = None;
+            cheque.vest_end = None;
+            // === when cheque is a claim link
+            cheque.claim_hash = Some(claim_hash(b"secret"));
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === * it raises an error
+            result = az_safe_send.top_up(0, 1);
             assert_eq!(
                 result,
                 Err(AzSafeSendError::UnprocessableEntity(
-                    "Amount must be greater than zero.".to_string()
+                    "Claim links can't be topped up.".to_string()
                 ))
             );
-            // == when new cheque id will be less than or equal to u32::MAX is within range
-            az_safe_send.cheques_total = u32::MAX - 1;
-            // === when token address is not provided
-            // ==== when fee is incorrect
-            let amount: Balance = 1;
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(az_safe_send.fee);
-            // ==== * it raises an error
-            result = az_safe_send.create(accounts.bob, amount, None, None, None, None);
-            assert_eq!(result, Err(AzSafeSendError::IncorrectFee));
-            // ==== when fee is correct
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
-                az_safe_send.fee + amount,
+            cheque.claim_hash = None;
+            // === when cheque is approver gated
+            cheque.approvers = vec![accounts.charlie];
+            cheque.approval_threshold = 1;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === * it raises an error
+            result = az_safe_send.top_up(0, 1);
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Approver gated cheques can't be topped up.".to_string()
+                ))
             );
-            // ==== * it stores the submitter as the caller
-            result = az_safe_send.create(accounts.bob, amount, None, memo.clone(), None, None);
-            let result_unwrapped = result.unwrap();
-            // ==== * it increases the cheque length by 1
-            assert_eq!(az_safe_send.cheques_total, u32::MAX);
-            // ==== * it stores the id as the current length
-            assert_eq!(result_unwrapped.id, u32::MAX - 1);
-            // ==== * it stores the caller as from
-            assert_eq!(result_unwrapped.from, admin());
-            // ==== * it stores the to
-            assert_eq!(result_unwrapped.to, accounts.bob);
-            // ==== * it stores the amount
-            assert_eq!(result_unwrapped.amount, amount);
-            // ==== * it sets the status to 0
-            assert_eq!(result_unwrapped.status, 0);
-            // ==== * it stores the submitted token_address
-            assert_eq!(result_unwrapped.token_address, None);
-            // ==== * it stores the submitted memo
-            assert_eq!(result_unwrapped.memo, memo);
-            // ==== * it stores the transaction
+            cheque.approvers = vec![];
+            cheque.approval_threshold = 0;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // === when extra amount is zero
+            // === * it raises an error
+            result = az_safe_send.top_up(0, 0);
             assert_eq!(
-                result_unwrapped,
-                az_safe_send.cheques.get(result_unwrapped.id).unwrap()
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount must be greater than zero.".to_string()
+                ))
             );
-            // == when new cheque id will be greater than u32::MAX
-            result = az_safe_send.create(accounts.bob, 1, Some(token_address()), None, None, None);
+            // === when new amount would overflow
+            // === * it raises an error
+            result = az_safe_send.top_up(0, Balance::MAX);
             assert_eq!(
                 result,
-                Err(AzSafeSendError::RecordsLimitReached("Cheque".to_string()))
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Amount is too large.".to_string()
+                ))
             );
+            // === when token address is not provided
+            // ==== when AZERO sent in doesn't equal the extra amount
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(5);
+            // ==== * it raises an error
+            result = az_safe_send.top_up(0, 6);
+            assert_eq!(result, Err(AzSafeSendError::IncorrectFee));
+            // ==== when AZERO sent in equals the extra amount
+            let result_unwrapped = az_safe_send.top_up(0, 5).unwrap();
+            // ==== * it increases the amount
+            assert_eq!(result_unwrapped.amount, MOCK_AMOUNT + 5);
+            // ==== * it stores the cheque
+            assert_eq!(result_unwrapped, az_safe_send.cheques.get(0).unwrap());
+        }
+
+        // Updating to a router is tested in e2e tests below
+        #[ink::test]
+        fn test_update_azero_id_router_address() {
+            let (accounts, mut az_safe_send) = init();
+            // when called by non-admin
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            // * it raises an error
+            let result = az_safe_send.update_azero_id_router_address(accounts.charlie);
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
         }
 
         #[ink::test]
@@ -632,6 +5809,66 @@ mod az_safe_send {
             // = * it updates the fee
             assert_eq!(az_safe_send.fee, 10);
         }
+
+        #[ink::test]
+        fn test_update_memo() {
+            let (accounts, mut az_safe_send) = init();
+            let memo = Some("345123".to_string());
+            // when cheque doesn't exist
+            let mut result = az_safe_send.update_memo(0, memo.clone());
+            // * it raises an error
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Cheque".to_string())));
+            // when cheque exists
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                MOCK_FEE + MOCK_AMOUNT,
+            );
+            let mut cheque: Cheque = az_safe_send
+                .create(
+                    Some(accounts.bob),
+                    MOCK_AMOUNT,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
+                .unwrap();
+            // = when cheque doesn't belong to caller
+            // = * it raises an error
+            set_caller::<DefaultEnvironment>(accounts.bob);
+            result = az_safe_send.update_memo(0, memo.clone());
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // = when cheque belongs to caller
+            set_caller::<DefaultEnvironment>(admin());
+            // == when cheque is not pending
+            cheque.status = ChequeStatus::Cancelled;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            // == * it raises an error
+            result = az_safe_send.update_memo(0, memo.clone());
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Status must be pending collection.".to_string()
+                ))
+            );
+            // == when cheque is pending
+            cheque.status = ChequeStatus::PendingCollection;
+            az_safe_send.cheques.insert(cheque.id, &cheque);
+            let result_unwrapped = az_safe_send.update_memo(0, memo.clone()).unwrap();
+            // == * it updates the memo
+            assert_eq!(result_unwrapped.memo, memo);
+            assert_eq!(az_safe_send.cheques.get(0).unwrap().memo, memo);
+        }
     }
 
     #[cfg(all(test, feature = "e2e-tests"))]
@@ -640,6 +5877,7 @@ mod az_safe_send {
         use crate::az_safe_send::AzSafeSendRef;
         use az_button::ButtonRef;
         use ink_e2e::{build_message, Keypair};
+        use mock_azero_id_router::MockAzeroIdRouterRef;
         use openbrush::contracts::traits::psp22::psp22_external::PSP22;
 
         // === CONSTANTS ===
@@ -662,6 +5900,109 @@ mod az_safe_send {
         }
 
         // === TEST HANDLES ===
+        #[ink_e2e::test]
+        async fn test_add_resolver(mut client: ::ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let bob_account_id: AccountId = account_id(ink_e2e::bob());
+
+            // Instantiate resolver
+            let router_constructor = MockAzeroIdRouterRef::new();
+            let router_id: AccountId = client
+                .instantiate(
+                    "mock_azero_id_router",
+                    &ink_e2e::alice(),
+                    router_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Router instantiate failed")
+                .account_id;
+            let set_address_message = build_message::<MockAzeroIdRouterRef>(router_id.clone())
+                .call(|router| router.set_address("bob.tzero".to_string(), bob_account_id));
+            client
+                .call(&ink_e2e::alice(), set_address_message, 0, None)
+                .await
+                .expect("set address failed");
+            // Instantiate safe send smart contract
+            let safe_send_constructor =
+                AzSafeSendRef::new(MOCK_FEE, mock_azero_id_router_address(ink_e2e::charlie()));
+            let safe_send_id: AccountId = client
+                .instantiate(
+                    "az_safe_send",
+                    &ink_e2e::alice(),
+                    safe_send_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Safe send instantiate failed")
+                .account_id;
+            // when called by admin
+            // = when resolver responds to the probe domain
+            let add_resolver_message =
+                build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
+                    safe_send.add_resolver(
+                        ".TZERO".to_string(),
+                        router_id,
+                        AZERO_ID_GET_ADDRESS_SELECTOR,
+                        ResolverReturnType::AccountIdResult,
+                        "bob.tzero".to_string(),
+                    )
+                });
+            client
+                .call(&ink_e2e::alice(), add_resolver_message, 0, None)
+                .await
+                .expect("add resolver failed");
+            // = * it stores the resolver against the TLD in lowercase without the leading dot
+            let resolver_message = build_message::<AzSafeSendRef>(safe_send_id.clone())
+                .call(|safe_send| safe_send.resolver("tzero".to_string()));
+            let resolver: Resolver = client
+                .call_dry_run(&ink_e2e::alice(), &resolver_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            assert_eq!(
+                resolver,
+                Resolver {
+                    address: router_id,
+                    selector: AZERO_ID_GET_ADDRESS_SELECTOR,
+                    return_type: ResolverReturnType::AccountIdResult,
+                }
+            );
+            // = * it resolves domains ending in the TLD
+            // The AZERO.ID router address isn't a contract, so this fails unless the resolver
+            // is used
+            let create_message =
+                build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
+                    safe_send.create_to_azero_id(
+                        "bob.tzero".to_string(),
+                        MOCK_SEND_AMOUNT,
+                        None,
+                        None,
+                        None,
+                    )
+                });
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    create_message,
+                    MOCK_FEE + MOCK_SEND_AMOUNT,
+                    None,
+                )
+                .await
+                .expect("create to AZERO.ID failed");
+            let show_message =
+                build_message::<AzSafeSendRef>(safe_send_id).call(|safe_send| safe_send.show(0));
+            let cheque: Cheque = client
+                .call_dry_run(&ink_e2e::alice(), &show_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            assert_eq!(cheque.to, Some(bob_account_id));
+
+            Ok(())
+        }
+
         // This is just to test when cheque has a token address associated with it
         #[ink_e2e::test]
         async fn test_cancel(mut client: ::ink_e2e::Client<C, E>) -> E2EResult<()> {
@@ -706,12 +6047,23 @@ mod az_safe_send {
             let create_message =
                 build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
                     safe_send.create(
-                        bob_account_id,
+                        Some(bob_account_id),
                         MOCK_SEND_AMOUNT,
                         Some(token_id),
                         None,
                         None,
                         None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        vec![],
+                        0,
+                        None,
+                        0,
+                        None,
+                        false,
                     )
                 });
             client
@@ -730,19 +6082,104 @@ mod az_safe_send {
             assert!(before_cancel_balance < after_cancel_balance);
             // = it returns the cheque amount to the user
             let balance_message = build_message::<ButtonRef>(token_id)
-                .call(|token| token.balance_of(alice_account_id));
+                .call(|token| token.balance_of(alice_account_id));
+            let balance: Balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, MOCK_AMOUNT);
+
+            Ok(())
+        }
+
+        // The primary reason is to test that when token address is present, token is sent to the collector
+        #[ink_e2e::test]
+        async fn test_collect(mut client: ::ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let bob_account_id: AccountId = account_id(ink_e2e::bob());
+
+            // Instantiate token
+            let token_constructor = ButtonRef::new(
+                MOCK_AMOUNT,
+                Some("Button".to_string()),
+                Some("BTN".to_string()),
+                6,
+            );
+            let token_id: AccountId = client
+                .instantiate("az_button", &ink_e2e::alice(), token_constructor, 0, None)
+                .await
+                .expect("Reward token instantiate failed")
+                .account_id;
+            // Instantiate safe send smart contract
+            let safe_send_constructor =
+                AzSafeSendRef::new(MOCK_FEE, mock_azero_id_router_address(ink_e2e::charlie()));
+            let safe_send_id: AccountId = client
+                .instantiate(
+                    "az_safe_send",
+                    &ink_e2e::alice(),
+                    safe_send_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Safe send instantiate failed")
+                .account_id;
+            // when token address is supplied
+            // = when fee is correct
+            // == when user has provided allowance to contract to acquire token and has sufficient balance
+            let increase_allowance_message = build_message::<ButtonRef>(token_id.clone())
+                .call(|token| token.increase_allowance(safe_send_id, u128::MAX));
+            client
+                .call(&ink_e2e::alice(), increase_allowance_message, 0, None)
+                .await
+                .expect("increase allowance failed");
+            let create_message =
+                build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
+                    safe_send.create(
+                        Some(bob_account_id),
+                        MOCK_SEND_AMOUNT,
+                        Some(token_id),
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        vec![],
+                        0,
+                        None,
+                        0,
+                        None,
+                        false,
+                    )
+                });
+            client
+                .call(&ink_e2e::alice(), create_message, MOCK_FEE, None)
+                .await
+                .expect("create failed");
+            // === when called by the collector
+            let collect_message = build_message::<AzSafeSendRef>(safe_send_id.clone())
+                .call(|safe_send| safe_send.collect(0));
+            client
+                .call(&ink_e2e::bob(), collect_message, 0, None)
+                .await
+                .expect("create failed");
+            // == * it sends the amount of token to the collector
+            let balance_message = build_message::<ButtonRef>(token_id)
+                .call(|button| button.balance_of(bob_account_id));
             let balance: Balance = client
                 .call_dry_run(&ink_e2e::alice(), &balance_message, 0, None)
                 .await
                 .return_value();
-            assert_eq!(balance, MOCK_AMOUNT);
+            assert_eq!(MOCK_SEND_AMOUNT, balance);
 
             Ok(())
         }
 
-        // The primary reason is to test that when token address is present, token is sent to the collector
+        // Cheques to an AZERO.ID only are payable to whoever the domain resolves to on collection
         #[ink_e2e::test]
-        async fn test_collect(mut client: ::ink_e2e::Client<C, E>) -> E2EResult<()> {
+        async fn test_collect_to_azero_id(mut client: ::ink_e2e::Client<C, E>) -> E2EResult<()> {
             let bob_account_id: AccountId = account_id(ink_e2e::bob());
 
             // Instantiate token
@@ -757,9 +6194,27 @@ mod az_safe_send {
                 .await
                 .expect("Reward token instantiate failed")
                 .account_id;
+            // Instantiate AZERO.ID router
+            let router_constructor = MockAzeroIdRouterRef::new();
+            let router_id: AccountId = client
+                .instantiate(
+                    "mock_azero_id_router",
+                    &ink_e2e::alice(),
+                    router_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Router instantiate failed")
+                .account_id;
+            let set_address_message = build_message::<MockAzeroIdRouterRef>(router_id.clone())
+                .call(|router| router.set_address("bob.azero".to_string(), bob_account_id));
+            client
+                .call(&ink_e2e::alice(), set_address_message, 0, None)
+                .await
+                .expect("set address failed");
             // Instantiate safe send smart contract
-            let safe_send_constructor =
-                AzSafeSendRef::new(MOCK_FEE, mock_azero_id_router_address(ink_e2e::charlie()));
+            let safe_send_constructor = AzSafeSendRef::new(MOCK_FEE, router_id);
             let safe_send_id: AccountId = client
                 .instantiate(
                     "az_safe_send",
@@ -771,9 +6226,6 @@ mod az_safe_send {
                 .await
                 .expect("Safe send instantiate failed")
                 .account_id;
-            // when token address is supplied
-            // = when fee is correct
-            // == when user has provided allowance to contract to acquire token and has sufficient balance
             let increase_allowance_message = build_message::<ButtonRef>(token_id.clone())
                 .call(|token| token.increase_allowance(safe_send_id, u128::MAX));
             client
@@ -783,26 +6235,44 @@ mod az_safe_send {
             let create_message =
                 build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
                     safe_send.create(
-                        bob_account_id,
+                        None,
                         MOCK_SEND_AMOUNT,
                         Some(token_id),
                         None,
+                        Some("bob.azero".to_string()),
                         None,
+                        Some(Timestamp::MAX),
                         None,
+                        None,
+                        None,
+                        None,
+                        vec![],
+                        0,
+                        None,
+                        0,
+                        None,
+                        false,
                     )
                 });
             client
                 .call(&ink_e2e::alice(), create_message, MOCK_FEE, None)
                 .await
                 .expect("create failed");
-            // === when called by the collector
+            // when called by someone the domain doesn't resolve to
+            // * it raises an error
             let collect_message = build_message::<AzSafeSendRef>(safe_send_id.clone())
                 .call(|safe_send| safe_send.collect(0));
+            let result = client
+                .call_dry_run(&ink_e2e::charlie(), &collect_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(AzSafeSendError::Unauthorised));
+            // when called by the address the domain resolves to
             client
                 .call(&ink_e2e::bob(), collect_message, 0, None)
                 .await
-                .expect("create failed");
-            // == * it sends the amount of token to the collector
+                .expect("collect failed");
+            // * it sends the amount of token to the collector
             let balance_message = build_message::<ButtonRef>(token_id)
                 .call(|button| button.balance_of(bob_account_id));
             let balance: Balance = client
@@ -810,6 +6280,131 @@ mod az_safe_send {
                 .await
                 .return_value();
             assert_eq!(MOCK_SEND_AMOUNT, balance);
+            // * it sets the status to collected
+            let show_message = build_message::<AzSafeSendRef>(safe_send_id.clone())
+                .call(|safe_send| safe_send.show(0));
+            let cheque: Cheque = client
+                .call_dry_run(&ink_e2e::alice(), &show_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            assert_eq!(cheque.status, ChequeStatus::Collected);
+
+            Ok(())
+        }
+
+        // Cheques with revalidate_azero_id set are only collectable while the domain still
+        // resolves to the recipient
+        #[ink_e2e::test]
+        async fn test_collect_with_revalidation(
+            mut client: ::ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let bob_account_id: AccountId = account_id(ink_e2e::bob());
+            let charlie_account_id: AccountId = account_id(ink_e2e::charlie());
+
+            // Instantiate AZERO.ID router
+            let router_constructor = MockAzeroIdRouterRef::new();
+            let router_id: AccountId = client
+                .instantiate(
+                    "mock_azero_id_router",
+                    &ink_e2e::alice(),
+                    router_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Router instantiate failed")
+                .account_id;
+            let set_address_message = build_message::<MockAzeroIdRouterRef>(router_id.clone())
+                .call(|router| router.set_address("bob.azero".to_string(), bob_account_id));
+            client
+                .call(&ink_e2e::alice(), set_address_message, 0, None)
+                .await
+                .expect("set address failed");
+            // Instantiate safe send smart contract
+            let safe_send_constructor = AzSafeSendRef::new(MOCK_FEE, router_id);
+            let safe_send_id: AccountId = client
+                .instantiate(
+                    "az_safe_send",
+                    &ink_e2e::alice(),
+                    safe_send_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Safe send instantiate failed")
+                .account_id;
+            // Create a cheque with revalidation and one without
+            for revalidate_azero_id in [true, false] {
+                let create_message =
+                    build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
+                        safe_send.create(
+                            Some(bob_account_id),
+                            MOCK_SEND_AMOUNT,
+                            None,
+                            None,
+                            Some("bob.azero".to_string()),
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            vec![],
+                            0,
+                            None,
+                            0,
+                            None,
+                            revalidate_azero_id,
+                        )
+                    });
+                client
+                    .call(
+                        &ink_e2e::alice(),
+                        create_message,
+                        MOCK_FEE + MOCK_SEND_AMOUNT,
+                        None,
+                    )
+                    .await
+                    .expect("create failed");
+            }
+            // when the domain no longer resolves to the recipient
+            let set_address_message = build_message::<MockAzeroIdRouterRef>(router_id.clone())
+                .call(|router| router.set_address("bob.azero".to_string(), charlie_account_id));
+            client
+                .call(&ink_e2e::alice(), set_address_message, 0, None)
+                .await
+                .expect("set address failed");
+            // = when cheque revalidates the domain
+            // = * it raises an error
+            let collect_message = build_message::<AzSafeSendRef>(safe_send_id.clone())
+                .call(|safe_send| safe_send.collect(0));
+            let result = client
+                .call_dry_run(&ink_e2e::bob(), &collect_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(
+                result,
+                Err(AzSafeSendError::UnprocessableEntity(
+                    "Domain does not belong to address".to_string()
+                ))
+            );
+            // = when cheque doesn't revalidate the domain
+            let collect_message = build_message::<AzSafeSendRef>(safe_send_id.clone())
+                .call(|safe_send| safe_send.collect(1));
+            client
+                .call(&ink_e2e::bob(), collect_message, 0, None)
+                .await
+                .expect("collect failed");
+            // = * it can still be collected by the recipient
+            let show_message = build_message::<AzSafeSendRef>(safe_send_id.clone())
+                .call(|safe_send| safe_send.show(1));
+            let cheque: Cheque = client
+                .call_dry_run(&ink_e2e::alice(), &show_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            assert_eq!(cheque.status, ChequeStatus::Collected);
 
             Ok(())
         }
@@ -849,7 +6444,25 @@ mod az_safe_send {
             // = when fee is incorrect
             // * it raises an error
             let create_message = build_message::<AzSafeSendRef>(safe_send_id).call(|safe_send| {
-                safe_send.create(bob_account_id, 1, Some(token_id), None, None, None)
+                safe_send.create(
+                    Some(bob_account_id),
+                    1,
+                    Some(token_id),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![],
+                    0,
+                    None,
+                    0,
+                    None,
+                    false,
+                )
             });
             let result = client
                 .call_dry_run(&ink_e2e::alice(), &create_message, 0, None)
@@ -867,12 +6480,23 @@ mod az_safe_send {
             let create_message =
                 build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
                     safe_send.create(
-                        bob_account_id,
+                        Some(bob_account_id),
                         MOCK_SEND_AMOUNT,
                         Some(token_id),
                         None,
                         None,
                         None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        vec![],
+                        0,
+                        None,
+                        0,
+                        None,
+                        false,
                     )
                 });
             client
@@ -900,15 +6524,246 @@ mod az_safe_send {
             // ==== * it stores the caller as from
             assert_eq!(cheque.from, alice_account_id);
             // ==== * it stores the to
-            assert_eq!(cheque.to, bob_account_id);
+            assert_eq!(cheque.to, Some(bob_account_id));
             // ==== * it stores the amount
             assert_eq!(cheque.amount, MOCK_SEND_AMOUNT);
-            // ==== * it sets the status to 0
-            assert_eq!(cheque.status, 0);
+            // ==== * it sets the status to pending collection
+            assert_eq!(cheque.status, ChequeStatus::PendingCollection);
             // ==== * it stores the submitted token_address
             assert_eq!(cheque.token_address, Some(token_id));
 
             Ok(())
         }
+
+        #[ink_e2e::test]
+        async fn test_create_to_azero_id(mut client: ::ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let alice_account_id: AccountId = account_id(ink_e2e::alice());
+            let bob_account_id: AccountId = account_id(ink_e2e::bob());
+
+            // Instantiate AZERO.ID router
+            let router_constructor = MockAzeroIdRouterRef::new();
+            let router_id: AccountId = client
+                .instantiate(
+                    "mock_azero_id_router",
+                    &ink_e2e::alice(),
+                    router_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Router instantiate failed")
+                .account_id;
+            let set_address_message = build_message::<MockAzeroIdRouterRef>(router_id.clone())
+                .call(|router| router.set_address("bob.azero".to_string(), bob_account_id));
+            client
+                .call(&ink_e2e::alice(), set_address_message, 0, None)
+                .await
+                .expect("set address failed");
+            let set_address_message = build_message::<MockAzeroIdRouterRef>(router_id.clone())
+                .call(|router| router.set_address("alice.azero".to_string(), alice_account_id));
+            client
+                .call(&ink_e2e::alice(), set_address_message, 0, None)
+                .await
+                .expect("set address failed");
+            // Instantiate safe send smart contract
+            let safe_send_constructor = AzSafeSendRef::new(MOCK_FEE, router_id);
+            let safe_send_id: AccountId = client
+                .instantiate(
+                    "az_safe_send",
+                    &ink_e2e::alice(),
+                    safe_send_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Safe send instantiate failed")
+                .account_id;
+            // when domain doesn't resolve to an address
+            // * it raises an error
+            let create_message =
+                build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
+                    safe_send.create_to_azero_id(
+                        "charlie.azero".to_string(),
+                        MOCK_SEND_AMOUNT,
+                        None,
+                        None,
+                        None,
+                    )
+                });
+            let result = client
+                .call_dry_run(
+                    &ink_e2e::alice(),
+                    &create_message,
+                    MOCK_FEE + MOCK_SEND_AMOUNT,
+                    None,
+                )
+                .await
+                .return_value();
+            assert_eq!(result, Err(AzSafeSendError::NotFound("Domain".to_string())));
+            // when domain resolves to an address
+            let create_message =
+                build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
+                    safe_send.create_to_azero_id(
+                        "bob.azero".to_string(),
+                        MOCK_SEND_AMOUNT,
+                        None,
+                        None,
+                        Some("alice.azero".to_string()),
+                    )
+                });
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    create_message,
+                    MOCK_FEE + MOCK_SEND_AMOUNT,
+                    None,
+                )
+                .await
+                .expect("create to AZERO.ID failed");
+            let show_message = build_message::<AzSafeSendRef>(safe_send_id.clone())
+                .call(|safe_send| safe_send.show(0));
+            let cheque: Cheque = client
+                .call_dry_run(&ink_e2e::alice(), &show_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            // * it stores the address the domain resolves to as the to
+            assert_eq!(cheque.to, Some(bob_account_id));
+            // * it stores the domain
+            assert_eq!(cheque.recipient_azero_id, Some("bob.azero".to_string()));
+            // * it stores the sender's AZERO.ID
+            assert_eq!(cheque.sender_azero_id, Some("alice.azero".to_string()));
+            // * it stores the amount
+            assert_eq!(cheque.amount, MOCK_SEND_AMOUNT);
+            // when creating a cheque with a receiver and both AZERO.IDs
+            let create_message =
+                build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
+                    safe_send.create(
+                        Some(bob_account_id),
+                        MOCK_SEND_AMOUNT,
+                        None,
+                        None,
+                        Some("bob.azero".to_string()),
+                        Some("alice.azero".to_string()),
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        vec![],
+                        0,
+                        None,
+                        0,
+                        None,
+                        false,
+                    )
+                });
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    create_message,
+                    MOCK_FEE + MOCK_SEND_AMOUNT,
+                    None,
+                )
+                .await
+                .expect("create failed");
+            let show_message =
+                build_message::<AzSafeSendRef>(safe_send_id).call(|safe_send| safe_send.show(1));
+            let cheque: Cheque = client
+                .call_dry_run(&ink_e2e::alice(), &show_message, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            // * it stores the validated AZERO.IDs
+            assert_eq!(cheque.recipient_azero_id, Some("bob.azero".to_string()));
+            assert_eq!(cheque.sender_azero_id, Some("alice.azero".to_string()));
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn test_update_azero_id_router_address(
+            mut client: ::ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Instantiate token
+            let token_constructor = ButtonRef::new(
+                MOCK_AMOUNT,
+                Some("Button".to_string()),
+                Some("BTN".to_string()),
+                6,
+            );
+            let token_id: AccountId = client
+                .instantiate("az_button", &ink_e2e::alice(), token_constructor, 0, None)
+                .await
+                .expect("Reward token instantiate failed")
+                .account_id;
+            // Instantiate AZERO.ID router
+            let router_constructor = MockAzeroIdRouterRef::new();
+            let router_id: AccountId = client
+                .instantiate(
+                    "mock_azero_id_router",
+                    &ink_e2e::alice(),
+                    router_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Router instantiate failed")
+                .account_id;
+            // Instantiate safe send smart contract
+            let safe_send_constructor =
+                AzSafeSendRef::new(MOCK_FEE, mock_azero_id_router_address(ink_e2e::charlie()));
+            let safe_send_id: AccountId = client
+                .instantiate(
+                    "az_safe_send",
+                    &ink_e2e::alice(),
+                    safe_send_constructor,
+                    0,
+                    None,
+                )
+                .await
+                .expect("Safe send instantiate failed")
+                .account_id;
+            // when called by admin
+            // = when address isn't a contract
+            // = * it raises an error
+            let update_message =
+                build_message::<AzSafeSendRef>(safe_send_id.clone()).call(|safe_send| {
+                    safe_send.update_azero_id_router_address(account_id(ink_e2e::bob()))
+                });
+            let mut result = client
+                .call_dry_run(&ink_e2e::alice(), &update_message, 0, None)
+                .await
+                .return_value();
+            assert!(matches!(result, Err(AzSafeSendError::InkEnvError(_))));
+            // = when address is a contract that doesn't respond to get_address
+            // = * it raises an error
+            let update_message = build_message::<AzSafeSendRef>(safe_send_id.clone())
+                .call(|safe_send| safe_send.update_azero_id_router_address(token_id));
+            result = client
+                .call_dry_run(&ink_e2e::alice(), &update_message, 0, None)
+                .await
+                .return_value();
+            assert!(matches!(result, Err(AzSafeSendError::InkEnvError(_))));
+            // = when address is a router
+            let update_message = build_message::<AzSafeSendRef>(safe_send_id.clone())
+                .call(|safe_send| safe_send.update_azero_id_router_address(router_id));
+            let update_result = client
+                .call(&ink_e2e::alice(), update_message, 0, None)
+                .await
+                .expect("update AZERO.ID router address failed");
+            // = * it emits an event
+            assert!(update_result.contains_event("Contracts", "ContractEmitted"));
+            // = * it updates the AZERO.ID router address
+            let config_message =
+                build_message::<AzSafeSendRef>(safe_send_id).call(|safe_send| safe_send.config());
+            let config: Config = client
+                .call_dry_run(&ink_e2e::alice(), &config_message, 0, None)
+                .await
+                .return_value();
+            assert_eq!(config.azero_id_router_address, router_id);
+
+            Ok(())
+        }
     }
 }
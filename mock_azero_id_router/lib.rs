@@ -0,0 +1,65 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::mock_azero_id_router::MockAzeroIdRouterRef;
+
+// Stands in for the AZERO.ID router in e2e tests
+#[ink::contract]
+pub mod mock_azero_id_router {
+    use ink::{prelude::string::String, storage::Mapping};
+
+    // === STRUCTS ===
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct MockAzeroIdRouter {
+        addresses: Mapping<String, AccountId>,
+    }
+
+    impl MockAzeroIdRouter {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        // Same selector and return type as the AZERO.ID router
+        #[ink(message)]
+        pub fn get_address(&self, domain: String) -> Result<AccountId, u8> {
+            self.addresses.get(domain).ok_or(0)
+        }
+
+        #[ink(message)]
+        pub fn set_address(&mut self, domain: String, address: AccountId) {
+            self.addresses.insert(domain, &address);
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{
+            test::{default_accounts, DefaultAccounts},
+            DefaultEnvironment,
+        };
+
+        // === HELPERS ===
+        fn init() -> (DefaultAccounts<DefaultEnvironment>, MockAzeroIdRouter) {
+            let accounts = default_accounts();
+            let mock_azero_id_router = MockAzeroIdRouter::new();
+            (accounts, mock_azero_id_router)
+        }
+
+        // === TEST HANDLES ===
+        #[ink::test]
+        fn test_get_address() {
+            let (accounts, mut mock_azero_id_router) = init();
+            // when domain has no address
+            // * it raises an error
+            let mut result = mock_azero_id_router.get_address("bob.azero".to_string());
+            assert_eq!(result, Err(0));
+            // when domain has an address
+            mock_azero_id_router.set_address("bob.azero".to_string(), accounts.bob);
+            // * it returns the address
+            result = mock_azero_id_router.get_address("bob.azero".to_string());
+            assert_eq!(result, Ok(accounts.bob));
+        }
+    }
+}